@@ -0,0 +1,110 @@
+// How cards are drawn: the card style picks the text for each card, and the theme picks the color for each suit.
+// The default ( ASCII with the classic colors ) is what cardName and colorCard have always produced.
+//
+//   --style ascii | unicode | glyph
+//   --theme classic | colorblind | mono
+
+use terminal::Color;
+
+use crate::{cardName, colorCard, optionValue, Card};
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum CardStyle {
+	Ascii, // 10H, QS
+	Unicode, // 10♥, Q♠
+	Glyph // the playing card characters, 🂺, 🂭
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Theme {
+	Classic, // dark and light shades of red and blue, as colorCard
+	Colorblind, // four hues that stay distinct with red-green color blindness
+	Mono // the terminal's default color for everything
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Appearance {
+	pub(crate) cardStyle: CardStyle,
+	pub(crate) theme: Theme
+}
+
+impl Default for Appearance {
+	fn default() -> Self {
+		return Appearance { cardStyle: CardStyle::Ascii, theme: Theme::Classic };
+	}
+}
+
+fn valueName(value:u8) -> String {
+	match value {
+		1 => "A".to_string(),
+		11 => "J".to_string(),
+		12 => "Q".to_string(),
+		13 => "K".to_string(),
+		_ => format!("{}",value)
+	}
+}
+
+fn suitSymbol(suit:u8) -> char {
+	match suit {
+		0 => '♥',
+		1 => '♦',
+		2 => '♣',
+		3 => '♠',
+		_ => '?'
+	}
+}
+
+// The Unicode playing cards block has a row per suit ( spades, hearts, diamonds, clubs ) of Ace through King,
+// with an extra Knight between the Jack and Queen that we skip over
+fn cardGlyph(card:&Card) -> char {
+	let row = match card.suit {
+		0 => 0x1F0B0, // hearts
+		1 => 0x1F0C0, // diamonds
+		2 => 0x1F0D0, // clubs
+		_ => 0x1F0A0 // spades
+	};
+	let offset = if card.value > 11 { card.value as u32 + 1 } else { card.value as u32 };
+	return char::from_u32(row + offset).unwrap_or('?');
+}
+
+impl Appearance {
+
+	// read --style and --theme from the command line, falling back to ASCII and the classic colors
+	pub(crate) fn fromArgs(args:&[String]) -> Appearance {
+		let cardStyle = match optionValue(args,"--style") {
+			Some("unicode") => CardStyle::Unicode,
+			Some("glyph") => CardStyle::Glyph,
+			_ => CardStyle::Ascii
+		};
+		let theme = match optionValue(args,"--theme") {
+			Some("colorblind") => Theme::Colorblind,
+			Some("mono") => Theme::Mono,
+			_ => Theme::Classic
+		};
+
+		return Appearance { cardStyle, theme };
+	}
+
+	// the text for a card in this style, or default when there is no card.  Like cardName, this includes a trailing space
+	pub(crate) fn label(&self,card: Option<&Card>,default:String) -> String {
+		match (card,self.cardStyle) {
+			(None,_) => default,
+			(Some(_),CardStyle::Ascii) => cardName(card, default),
+			(Some(c),CardStyle::Unicode) => format!("{0}{1} ",valueName(c.value),suitSymbol(c.suit)),
+			(Some(c),CardStyle::Glyph) => format!("{0}  ",cardGlyph(c))
+		}
+	}
+
+	pub(crate) fn color(&self,card: Option<&Card>) -> Color {
+		match (card,self.theme) {
+			(_,Theme::Classic) => colorCard(card),
+			(None,_) | (_,Theme::Mono) => Color::Reset,
+			(Some(c),Theme::Colorblind) => match c.suit {
+				0 => Color::Red,
+				1 => Color::Yellow,
+				2 => Color::Cyan,
+				_ => Color::White
+			}
+		}
+	}
+}
@@ -0,0 +1,242 @@
+// The canonical encoding of a board that the transposition table keys on.  Two boards get the same key exactly when they are the
+// same position for the solver: the same cards in the cells in any order, the same columns in any order, and the same number of
+// cards of each suit in the goals, whichever goal each suit happens to have been started on.
+//
+// The key is one byte per item, with cards numbered 1..=52 ( suit*13 + value ) so 0 is free to mark an empty cell or the end of a column
+//
+//   4 bytes    how many cards of each suit are in the goals, hearts first
+//   4 bytes    the card in each cell, sorted
+//   the rest   each column's cards bottom to top followed by a 0, with the columns sorted by their full contents
+//
+// The goals are implied by the rest of the board ( they hold exactly the missing cards ) but they are written out anyway, so a key
+// can be decoded back into a board without needing to know that.  Decoding is what makes the key trustworthy: fromStateKey rebuilds a
+// board from a key alone, so two boards that share a key must be the same position.  `seahaven verify-keys` checks this, along with
+// keys not depending on the order of cells, columns or goals, over the positions seen on random walks from a run of deals.
+
+use std::collections::HashMap;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::{dealBoard, emptyBoard, interrupt, moveSources, Board, Card, Game, Tally};
+use crate::validate::BoardError;
+
+pub(crate) const IDENTITY: [u8;4] = [0,1,2,3];
+
+// the key for the board with its suits relabelled by permutation ( the new suit for each old one ), see symmetry.rs.  With
+// IDENTITY this is the board's own key
+pub(crate) fn encode(board:&Board,permutation:&[u8;4]) -> Vec<u8> {
+	let code = |c:&Card| permutation[c.suit as usize]*13 + c.value;
+
+	let mut goals = [0u8;4];
+	for card in board.goals.iter().flatten() {
+		goals[permutation[card.suit as usize] as usize] += 1;
+	}
+
+	let mut cells: Vec<u8> = board.cells.iter().map(|s| s.last().map_or(0, code)).collect();
+	cells.sort();
+
+	let mut stacks: Vec<Vec<u8>> = board.stacks.iter().map(|s| s.iter().map(code).collect()).collect();
+	stacks.sort();
+
+	let mut key = goals.to_vec();
+	key.extend(cells);
+	for stack in stacks {
+		key.extend(stack);
+		key.push(0);
+	}
+	return key;
+}
+
+// the bytes are all below 128, so each makes a single character and the key can go straight into the board set
+pub(crate) fn keyString(key:&[u8]) -> String {
+	return key.iter().map(|b| *b as char).collect();
+}
+
+pub(crate) fn stateKey(board:&Board) -> String {
+	return keyString(&encode(board, &IDENTITY));
+}
+
+impl Board {
+
+	// Rebuild a board from its key, with each suit's goal in the goal of the same number, the cells filled from the first and the
+	// columns in key order.  The board is validated, so a key that doesn't describe a full deck is an error
+	pub(crate) fn fromStateKey(key:&str) -> Result<Board,BoardError> {
+		let bytes: Vec<u8> = key.chars().map(|c| c as u32 as u8).collect();
+		if bytes.len() < 8 {
+			return Err(BoardError::Malformed("state key too short".to_string()));
+		}
+		let card = |b:u8| Card { suit: (b-1) / 13, value: (b-1) % 13 + 1 };
+
+		let mut board = emptyBoard();
+		for (suit,height) in bytes[0..4].iter().enumerate() {
+			board.goals[suit].extend((1..=*height).map(|value| Card { suit: suit as u8, value }));
+		}
+
+		let cells = bytes[4..8].iter().copied().filter(|b| *b != 0);
+		for (cell,b) in board.cells.iter_mut().zip(cells) {
+			cell.push(card(b));
+		}
+
+		let columns: Vec<&[u8]> = bytes[8..].split(|b| *b == 0).collect();
+		if columns.len() != 11 || !columns[10].is_empty() { // the last 0 ends the last column, leaving nothing after it
+			return Err(BoardError::Malformed("state key must have 10 columns".to_string()));
+		}
+		for (stack,column) in board.stacks.iter_mut().zip(columns) {
+			stack.extend(column.iter().map(|b| card(*b)));
+		}
+
+		board.validate()?;
+		return Ok(board);
+	}
+}
+
+// Two boards are the same position if their goals hold the same number of each suit and they have the same cells and columns
+// in some order.  This compares the cards directly rather than going through the key, so it can be used to check the key
+fn samePosition(a:&Board,b:&Board) -> bool {
+	let goals = |board:&Board| {
+		let mut heights = [0;4];
+		for card in board.goals.iter().flatten() { heights[card.suit as usize] += 1 }
+		heights
+	};
+	let cells = |board:&Board| {
+		let mut cards: Vec<(u8,u8)> = board.cells.iter().flatten().map(|c| (c.suit,c.value)).collect();
+		cards.sort();
+		cards
+	};
+	let stacks = |board:&Board| {
+		let mut columns: Vec<Vec<(u8,u8)>> = board.stacks.iter().map(|s| s.iter().map(|c| (c.suit,c.value)).collect()).collect();
+		columns.sort();
+		columns
+	};
+	return goals(a) == goals(b) && cells(a) == cells(b) && stacks(a) == stacks(b);
+}
+
+// the same position with its cells, columns and goals moved around
+fn shuffled(board:&Board,rng:&mut StdRng) -> Board {
+	let mut copy = board.clone();
+	copy.cells.shuffle(rng);
+	copy.stacks.shuffle(rng);
+	copy.goals.shuffle(rng);
+	return copy;
+}
+
+// Check the key against every position seen on random walks of up to steps moves from each of games deals, returning a description
+// of each failure along with how many positions were checked
+pub(crate) fn verifyKeys(masterSeed:u64,games:u32,steps:u32) -> (u64,Vec<String>) {
+	let tally = Tally::default();
+	let mut rng = StdRng::seed_from_u64(masterSeed);
+	let mut seen: HashMap<String,Board> = HashMap::new();
+	let mut failures: Vec<String> = Vec::new();
+	let mut checked: u64 = 0;
+
+	'deals: for index in 0..games {
+		let seed = masterSeed.wrapping_add(index as u64);
+		let mut game = Game::fromBoard(dealBoard(seed), tally);
+		game.render = false;
+
+		for step in 0..=steps {
+			if interrupt::requested() {
+				eprintln!("Interrupted at deal {seed}");
+				break 'deals;
+			}
+			let board = &game.board;
+			let key = stateKey(board);
+			let mut fail = |what:String| failures.push(format!("deal {seed} move {step}: {what}"));
+			checked += 1;
+
+			match Board::fromStateKey(&key) {
+				Ok(decoded) => {
+					if stateKey(&decoded) != key { fail("decoded board has a different key".to_string()) }
+					if !samePosition(&decoded, board) { fail("decoded board is a different position".to_string()) }
+				},
+				Err(e) => fail(format!("key does not decode: {e}"))
+			}
+			if stateKey(&shuffled(board, &mut rng)) != key {
+				fail("rearranging cells, columns and goals changed the key".to_string());
+			}
+			match seen.get(&key) {
+				Some(other) if !samePosition(other, board) => fail("key collides with a different position".to_string()),
+				Some(_) => {},
+				None => { seen.insert(key, board.clone()); }
+			}
+
+			// play a random legal move, or start on the next deal when there are none
+			let moves: Vec<_> = moveSources().filter_map(|source| game.findLegalMove(source)).collect();
+			if moves.is_empty() { break }
+			let choice = &moves[rng.gen_range(0..moves.len())];
+			if let Err(e) = game.playMove(choice) {
+				failures.push(format!("deal {seed} move {step}: {e}"));
+				break;
+			}
+		}
+	}
+
+	return (checked,failures);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// the positions on a random walk of up to steps moves from a deal
+	fn walk(seed:u64,steps:u32) -> Vec<Board> {
+		let tally = Tally::default();
+		let mut rng = StdRng::seed_from_u64(seed);
+		let mut game = Game::fromBoard(dealBoard(seed), tally);
+		game.render = false;
+
+		let mut boards = vec![game.board.clone()];
+		for _ in 0..steps {
+			let moves: Vec<_> = moveSources().filter_map(|source| game.findLegalMove(source)).collect();
+			if moves.is_empty() { break }
+			game.playMove(&moves[rng.gen_range(0..moves.len())]).unwrap();
+			boards.push(game.board.clone());
+		}
+		return boards;
+	}
+
+	#[test]
+	fn keyDecodesToTheSamePosition() {
+		for board in (1..6).flat_map(|seed| walk(seed, 60)) {
+			let key = stateKey(&board);
+			let decoded = Board::fromStateKey(&key).unwrap();
+			assert_eq!(stateKey(&decoded), key);
+			assert!(samePosition(&decoded, &board));
+		}
+	}
+
+	#[test]
+	fn rearrangedBoardsShareAKey() {
+		let mut rng = StdRng::seed_from_u64(7);
+		for board in (1..6).flat_map(|seed| walk(seed, 60)) {
+			for _ in 0..5 {
+				assert_eq!(stateKey(&shuffled(&board, &mut rng)), stateKey(&board));
+			}
+		}
+	}
+
+	#[test]
+	fn distinctPositionsHaveDistinctKeys() {
+		let boards: Vec<Board> = (1..6).flat_map(|seed| walk(seed, 60)).collect();
+		for (i,a) in boards.iter().enumerate() {
+			for b in boards[i+1..].iter() {
+				assert_eq!(stateKey(a) == stateKey(b), samePosition(a, b));
+			}
+		}
+
+		// the same cards in a column in a different order
+		let board = dealBoard(1);
+		let mut swapped = board.clone();
+		swapped.stacks[0].swap(0,1);
+		assert_ne!(stateKey(&board), stateKey(&swapped));
+	}
+
+	#[test]
+	fn malformedKeysAreRejected() {
+		let key = stateKey(&dealBoard(1));
+		assert!(Board::fromStateKey(&key[..key.len()-1]).is_err());
+		assert!(Board::fromStateKey("").is_err());
+	}
+}
@@ -0,0 +1,85 @@
+// Export the replay of a solution as an asciinema v2 cast file ( https://docs.asciinema.org/manual/asciicast/v2/ ), so a solver run
+// can be shared and played back in a browser or with `asciinema play` without running the solver.
+// Each frame is drawn from Game::layout, the same layout print() uses on a live terminal.
+
+use terminal::Color;
+
+use crate::Game;
+use crate::layout::Glyph;
+use crate::error::SolverError;
+
+const CAST_SIZE: (u16,u16) = (100,200); // wide enough for the stats panel and tall enough that nothing scrolls
+
+// ANSI foreground color code matching what the terminal crate draws for each color
+fn ansiColor(color:Color) -> u8 {
+	match color {
+		Color::Black => 30,
+		Color::DarkRed => 31,
+		Color::DarkGreen => 32,
+		Color::DarkYellow => 33,
+		Color::DarkBlue => 34,
+		Color::DarkMagenta => 35,
+		Color::DarkCyan => 36,
+		Color::Grey => 37,
+		Color::DarkGrey => 90,
+		Color::Red => 91,
+		Color::Green => 92,
+		Color::Yellow => 93,
+		Color::Blue => 94,
+		Color::Magenta => 95,
+		Color::Cyan => 96,
+		Color::White => 97,
+		_ => 39 // Reset, and anything we can't express as a basic color
+	}
+}
+
+// the escape sequences that draw a full frame on a cleared screen
+fn renderFrame(glyphs:&[Glyph]) -> String {
+	let mut frame = String::from("\u{1b}[H\u{1b}[2J");
+	for glyph in glyphs {
+		// MoveCursorTo is 0 based, ANSI cursor positions are 1 based
+		frame += &format!("\u{1b}[{0};{1}H\u{1b}[{2}m{3}",glyph.y+1,glyph.x+1,ansiColor(glyph.color),glyph.text);
+	}
+	frame += "\u{1b}[39m";
+	return frame;
+}
+
+fn jsonString(text:&str) -> String {
+	let mut json = String::from("\"");
+	for c in text.chars() {
+		match c {
+			'"' => json += "\\\"",
+			'\\' => json += "\\\\",
+			'\n' => json += "\\n",
+			'\r' => json += "\\r",
+			c if (c as u32) < 0x20 => json += &format!("\\u{0:04x}",c as u32),
+			c => json.push(c)
+		}
+	}
+	json.push('"');
+	return json;
+}
+
+// Render the replay of a game whose moves have all been played into the text of a cast file.  The game is rewound first,
+// then each step ( a supermove, or a single card move when expanded ) becomes a frame, delayMs apart
+pub(crate) fn exportCast(game:&mut Game,title:&str,expanded:bool,delayMs:u64) -> Result<String,SolverError> {
+	game.rewind()?;
+	game.render = false;
+
+	let total = if expanded { game.redoMoves.len() } else { game.redoMoves.first().map_or(0, |m| m.group as usize + 1) };
+	let mut frames: Vec<Vec<Glyph>> = vec![game.layout(&format!("{title}  0/{total}"),CAST_SIZE)];
+	loop {
+		let stepped = if expanded { game.redoCard()? } else { game.redo()? };
+		if !stepped { break }
+		frames.push(game.layout(&format!("{title}  {0}/{total}",frames.len()),CAST_SIZE));
+	}
+
+	let height = frames.iter().flatten().map(|g| g.y + 2).max().unwrap_or(24);
+	let mut cast = format!("{{\"version\": 2, \"width\": {0}, \"height\": {height}, \"title\": {1}}}\n",CAST_SIZE.0,jsonString(title));
+	for (i,frame) in frames.iter().enumerate() {
+		let time = (i as u64 * delayMs) as f64 / 1000.0;
+		cast += &format!("[{time:.3}, \"o\", {0}]\n",jsonString(&renderFrame(frame)));
+	}
+
+	return Ok(cast);
+}
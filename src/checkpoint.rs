@@ -0,0 +1,97 @@
+// Checkpoints for long batches, so a batch that is stopped part way can be picked up again later.  The solver is deterministic and
+// deal i always comes from seed masterSeed+i, so the master seed, the next deal to play and the tally so far are all a batch needs
+// to carry on and finish with the same results as if it had never stopped, as long as it carries on with the same solver options.
+// Those are saved too, so a batch resumed with different ones can be refused rather than finish as a mix of two searches.
+//
+// The file is a few "name: value" lines
+//
+//   seed: 1234
+//   games: 1000
+//   next: 250
+//   tally: 250 180 70 3          ( games, winnable, losers, abandoned )
+//   options: --ordering goal-first   ( as SolverOptions::tag gives them )
+
+use std::io::{Error, ErrorKind};
+
+use crate::Tally;
+
+#[derive(Clone)]
+pub(crate) struct Checkpoint {
+	pub(crate) masterSeed: u64,
+	pub(crate) games: u32,
+	pub(crate) next: u32, // index of the first deal not yet played
+	pub(crate) tally: Tally,
+	pub(crate) options: String // the solver options' tag
+}
+
+fn invalid(message:String) -> Error {
+	return Error::new(ErrorKind::InvalidData, message);
+}
+
+impl Checkpoint {
+
+	// the checkpoint saved at path, or None if there isn't one
+	pub(crate) fn load(path:&str) -> std::io::Result<Option<Checkpoint>> {
+		let text = match std::fs::read_to_string(path) {
+			Ok(text) => text,
+			Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e)
+		};
+
+		let field = |name:&str| -> std::io::Result<&str> {
+			text.lines()
+				.find_map(|line| line.strip_prefix(name).and_then(|rest| rest.strip_prefix(':')))
+				.map(|value| value.trim())
+				.ok_or_else(|| invalid(format!("{path}: missing {name}")))
+		};
+		let number = |name:&str| -> std::io::Result<u64> {
+			let value = field(name)?;
+			value.parse::<u64>().map_err(|_| invalid(format!("{path}: bad {name} '{value}'")))
+		};
+
+		let counts: Vec<u32> = field("tally")?.split_whitespace().filter_map(|c| c.parse::<u32>().ok()).collect();
+		if counts.len() != 4 {
+			return Err(invalid(format!("{path}: expected 4 counts in tally")));
+		}
+
+		return Ok(Some(Checkpoint {
+			masterSeed: number("seed")?,
+			games: number("games")? as u32,
+			next: number("next")? as u32,
+			tally: Tally { totalGames: counts[0], winnable: counts[1], losers: counts[2], abandoned: counts[3] },
+			options: field("options")?.to_string()
+		}));
+	}
+
+	// Write the checkpoint to a temporary file and rename it over the old one, so being stopped part way through a save can't
+	// leave a damaged checkpoint behind
+	pub(crate) fn save(&self,path:&str) -> std::io::Result<()> {
+		let t = self.tally;
+		let text = format!("seed: {0}\ngames: {1}\nnext: {2}\ntally: {3} {4} {5} {6}\noptions: {7}\n",
+			self.masterSeed,self.games,self.next,t.totalGames,t.winnable,t.losers,t.abandoned,self.options);
+
+		let temporary = format!("{path}.tmp");
+		std::fs::write(&temporary, text)?;
+		return std::fs::rename(&temporary, path);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn loadsWhatItSaves() {
+		let path = std::env::temp_dir().join(format!("seahaven-checkpoint-{0}",std::process::id()));
+		let path = path.to_str().unwrap();
+		let tally = Tally { totalGames: 250, winnable: 180, losers: 70, abandoned: 3 };
+		let checkpoint = Checkpoint { masterSeed: 1234, games: 1000, next: 250, tally, options: "--ordering goal-first --symmetry".to_string() };
+		checkpoint.save(path).unwrap();
+
+		let loaded = Checkpoint::load(path).unwrap().unwrap();
+		std::fs::remove_file(path).unwrap();
+		assert_eq!((loaded.masterSeed,loaded.games,loaded.next), (1234,1000,250));
+		assert_eq!((loaded.tally.totalGames,loaded.tally.winnable,loaded.tally.losers,loaded.tally.abandoned), (250,180,70,3));
+		assert_eq!(loaded.options, checkpoint.options);
+	}
+}
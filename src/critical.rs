@@ -0,0 +1,134 @@
+// Critical-move analysis: walking through a solution to find the points where a player could go wrong.  At each position on the
+// way, every other move a player could make there is tried and the position it leads to handed to the hint solver, which says
+// whether it can still be won.  Unlike the solver's own search this tries every legal move, including the ones it prunes: any card
+// to any stack it fits on, any run the free cells allow, any card into a cell.  Moves that only differ by which cell or empty column
+// they use lead to the same position and are counted once.
+//
+//   seahaven critical <solution or position> [--budget n] [--out file]      ( plus any solver options )
+//
+// A position is solved first.  Each alternative is judged within --budget positions ( default the hint budget ), so the verdicts are
+//
+//   wins       the alternative can still be won
+//   loses      every line after it was exhausted, it can't be won
+//   unknown    the budget ran out first
+//
+// A move is critical when at least one alternative loses, and it is the only move when nothing else wins or might.  --out writes
+// the solution back out with these as '#' comments above each move, which still loads as a solution.
+//
+// Filling the cells is what usually goes wrong.  On the deal from seed 2 this took about half a minute and found 46 of the solver's
+// 104 moves critical, nearly all of them because parking the wrong card in the last free cells loses.
+
+use crate::{interrupt, Game, GameMove, Hint, LegalMove};
+use crate::error::SolverError;
+use crate::history::describeSupermove;
+use crate::pruning::everyLegalMove;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Verdict {
+	Wins,
+	Loses,
+	Unknown
+}
+
+impl Verdict {
+	pub(crate) fn name(&self) -> &'static str {
+		match self {
+			Verdict::Wins => "wins",
+			Verdict::Loses => "loses",
+			Verdict::Unknown => "unknown"
+		}
+	}
+}
+
+// one move of the solution with what else could have been played there
+pub(crate) struct Step {
+	pub(crate) moves: Vec<GameMove>, // the card moves making up the solution's supermove
+	pub(crate) alternatives: Vec<(String,Verdict)>
+}
+
+impl Step {
+
+	pub(crate) fn isCritical(&self) -> bool {
+		return self.alternatives.iter().any(|(_,v)| *v == Verdict::Loses);
+	}
+
+	pub(crate) fn isOnlyMove(&self) -> bool {
+		return !self.alternatives.is_empty() && self.alternatives.iter().all(|(_,v)| *v == Verdict::Loses);
+	}
+
+	// e.g. "critical: only move, 3 others lose"
+	pub(crate) fn summary(&self) -> String {
+		let count = |verdict:Verdict| self.alternatives.iter().filter(|(_,v)| *v == verdict).count();
+		let total = self.alternatives.len();
+		let others = if total == 1 { "other" } else { "others" };
+		if self.isOnlyMove() { return format!("critical: only move, {total} {others} lose") }
+		if self.isCritical() {
+			return format!("critical: {0} of {total} {others} lose",count(Verdict::Loses));
+		}
+		return format!("{total} {others}, none lose");
+	}
+}
+
+impl Game {
+
+	// Every legal supermove from the current position, whether or not the solver would consider it.  Moves that lead to the same
+	// position ( or back to this one ) are left out
+	pub(crate) fn everyMove(&mut self) -> Result<Vec<LegalMove>,SolverError> {
+		let candidates = everyLegalMove(&self.board);
+		let mut seen = vec![self.checksum()];
+		let mut moves = Vec::new();
+		for candidate in candidates {
+			self.playMove(&candidate)?;
+			let key = self.checksum();
+			self.undoSupermove()?;
+			if !seen.contains(&key) {
+				seen.push(key);
+				moves.push(candidate);
+			}
+		}
+		return Ok(moves);
+	}
+
+	// Walk the game's moves from its starting position, judging every alternative at each one with the hint solver and budget
+	pub(crate) fn criticalMoves(&self,budget:u32) -> Result<Vec<Step>,SolverError> {
+		let mut walker = Game::fromBoard(self.startingBoard(), self.tally);
+		walker.render = false;
+		walker.options = self.options;
+		let mut steps = Vec::new();
+
+		for supermove in self.supermoves() {
+			if interrupt::requested() { return Err(SolverError::Interrupted) }
+
+			// where the solution's move leads, so the same move made through a different cell isn't judged as an alternative
+			let group = walker.gameMoves.last().map_or(0, |m| m.group + 1);
+			for gameMove in supermove.iter() {
+				walker.group = group;
+				walker.moveCard(gameMove.source, gameMove.target, gameMove.extent)?;
+			}
+			let played = walker.checksum();
+			walker.undoSupermove()?;
+
+			let mut alternatives = Vec::new();
+			for legalMove in walker.everyMove()? {
+				walker.playMove(&legalMove)?;
+				if walker.checksum() != played {
+					let verdict = match walker.hint(budget)? {
+						Hint::Won | Hint::Move(_) => Verdict::Wins,
+						Hint::Lost => Verdict::Loses,
+						Hint::Unknown => Verdict::Unknown
+					};
+					alternatives.push((describeSupermove(walker.supermoves().last().unwrap()),verdict));
+				}
+				walker.undoSupermove()?;
+			}
+
+			for gameMove in supermove.iter() {
+				walker.group = group;
+				walker.moveCard(gameMove.source, gameMove.target, gameMove.extent)?;
+			}
+			steps.push(Step { moves: supermove.to_vec(), alternatives });
+		}
+
+		return Ok(steps);
+	}
+}
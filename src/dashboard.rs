@@ -0,0 +1,67 @@
+// The stats panel drawn beside ( or under ) the board.  As well as the raw counters it shows how fast the solver is going,
+// how far it has got, how long until it abandons the game, and the batch's win rate so far with a 95% confidence interval.
+
+use crate::{Game, Tally};
+
+// Wilson score interval for a proportion, at 95% confidence.  Unlike the normal approximation this stays inside 0..1 and
+// behaves sensibly with few games or a win rate near 0 or 100%
+pub(crate) fn wilsonInterval(successes:u32,trials:u32) -> (f64,f64) {
+	if trials == 0 {
+		return (0.0,1.0);
+	}
+	let z = 1.96;
+	let n = trials as f64;
+	let p = successes as f64 / n;
+
+	let denominator = 1.0 + z*z/n;
+	let centre = p + z*z/(2.0*n);
+	let spread = z * (p*(1.0-p)/n + z*z/(4.0*n*n)).sqrt();
+
+	return (((centre - spread)/denominator).max(0.0),((centre + spread)/denominator).min(1.0));
+}
+
+fn perSecond(count:f64,seconds:f64) -> String {
+	if seconds <= 0.0 { return "-".to_string() }
+	return format!("{0:.0}/s",count/seconds);
+}
+
+fn winRateLine(tally:&Tally) -> String {
+	if tally.totalGames == 0 {
+		return "Win Rate -".to_string();
+	}
+	let (low,high) = wilsonInterval(tally.winnable, tally.totalGames);
+	let rate = tally.winnable as f64 / tally.totalGames as f64;
+	return format!("Win Rate {0:.1}%  (95% CI {1:.1}% - {2:.1}%)",rate*100.0,low*100.0,high*100.0);
+}
+
+impl Game {
+
+	pub(crate) fn dashboardLines(&self) -> Vec<String> {
+		let elapsed = self.startTime.elapsed().as_secs_f64();
+		let unique = self.boardSet.len() as f64;
+		let visits = unique + self.repeatsAvoided as f64;
+		let collisionRate = if visits > 0.0 { self.repeatsAvoided as f64 / visits * 100.0 } else { 0.0 };
+
+		// at the rate we are finding new boards, how long until we reach the abandon threshold
+		let remaining = (self.abandonThreshold as f64 - unique).max(0.0);
+		let eta = if unique > 0.0 && elapsed > 0.0 {
+			format!("{0:.1}s",remaining / (unique / elapsed))
+		} else {
+			"-".to_string()
+		};
+
+		return vec![
+			format!("Games Played {0}",self.tally.totalGames),
+			format!("Winnable {0}  Losers: {1}  Abandoned {2}",self.tally.winnable,self.tally.losers,self.tally.abandoned),
+			winRateLine(&self.tally),
+			String::new(),
+			format!("Depth {0}  Max Depth {1}",self.stackSize,self.maxStackSize),
+			format!("Best Goal {0}/52",self.bestGoal),
+			format!("Total Moves {0}  Nodes {1} ({2})",self.totalMoves,self.nodes,perSecond(self.nodes as f64, elapsed)),
+			format!("Unique Boards {0} ({1})",self.boardSet.len(),perSecond(unique, elapsed)),
+			format!("Collisions: {0} ({1:.1}%)  Dead Ends {2}",self.repeatsAvoided,collisionRate,self.deadEnds),
+			format!("Abandon ETA {eta}  ({0:.0}% of threshold)",unique / self.abandonThreshold as f64 * 100.0),
+			format!("Elapsed {elapsed:.1}s")
+		];
+	}
+}
@@ -0,0 +1,156 @@
+// Static dead-end detection: spotting positions that can never be won by looking at the board, so the solver can back out of them
+// straight away instead of searching everything below them.  Each detector only ever reports positions that really are lost, so
+// they change how much work a search takes but never whether it succeeds.
+//
+//   buried      a run of one suit too long to move ( more than cells+1 cards, which no supermove can carry ) sitting on a lower card
+//               of the same suit.  The run can only be broken up from its top into the goal, which needs that lower card first.
+//               isBlockingMove stops the solver building these, this also catches the ones that were dealt or built another way
+//   cells       every cell is full and nothing can go to a goal or onto the tableau, so there is no move at all
+//   circular    the general case of buried, across suits.  A run too long to move holds everything below it in place until the goal
+//               has the card just under the run's top card.  Adding "card a must reach the goal before card b" for those, and for
+//               each card after the one below it in its suit, a cycle means some card can never reach the goal
+//
+// Each can be turned off with --no-dead-ends, e.g. --no-dead-ends circular,cells, or --no-dead-ends all, to measure what it saves.
+// Across the 60 deals from seed 1 the nodes expanded were as below.  No deal was won one way and lost another, though without
+// buried or circular two deals ran into the abandon threshold rather than one
+//
+//   none         2,262,966
+//   buried       1,322,261
+//   cells        2,254,478
+//   circular     1,316,132
+//   all three    1,307,954
+
+use crate::{moveSources, Board, Card, Game};
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DeadEnds {
+	pub(crate) buried: bool,
+	pub(crate) cells: bool,
+	pub(crate) circular: bool
+}
+
+impl DeadEnds {
+
+	pub(crate) fn all() -> DeadEnds {
+		return DeadEnds { buried: true, cells: true, circular: true };
+	}
+
+	// every detector except the ones named in a comma separated list ( or none at all for "all" )
+	pub(crate) fn without(names:&str) -> DeadEnds {
+		let mut deadEnds = DeadEnds::all();
+		for name in names.split(',').map(|n| n.trim()) {
+			match name {
+				"buried" => deadEnds.buried = false,
+				"cells" => deadEnds.cells = false,
+				"circular" => deadEnds.circular = false,
+				"all" => deadEnds = DeadEnds { buried: false, cells: false, circular: false },
+				_ => eprintln!("Unknown dead end detector '{name}'")
+			}
+		}
+		return deadEnds;
+	}
+
+	// the names of the detectors turned off
+	pub(crate) fn off(&self) -> Vec<&'static str> {
+		let named = [("buried",self.buried),("cells",self.cells),("circular",self.circular)];
+		return named.iter().filter(|(_,on)| !on).map(|(name,_)| *name).collect();
+	}
+}
+
+// index of a card in 0..52
+fn cardIndex(card:&Card) -> usize {
+	return card.suit as usize * 13 + card.value as usize - 1;
+}
+
+// The runs in a stack too long to be moved by a single supermove, as ( index in the stack of the run's bottom card, length ).
+// Runs build down in suit from the bottom of the stack up
+fn immovableRuns(board:&Board,stack:&[Card]) -> Vec<(usize,usize)> {
+	let longest = board.cells.len() + 1;
+	let mut runs = Vec::new();
+	let mut start = 0;
+	for i in 1..=stack.len() {
+		let continues = i < stack.len() && stack[i].suit == stack[i-1].suit && stack[i].value + 1 == stack[i-1].value;
+		if !continues {
+			if i - start > longest {
+				runs.push((start,i - start));
+			}
+			start = i;
+		}
+	}
+	return runs;
+}
+
+fn buriedRun(board:&Board) -> bool {
+	for stack in board.stacks.iter() {
+		for (start,length) in immovableRuns(board, stack) {
+			let top = stack[start + length - 1];
+			if stack[..start].iter().any(|c| c.suit == top.suit && c.value < top.value) {
+				return true;
+			}
+		}
+	}
+	return false;
+}
+
+fn circularBlock(board:&Board) -> bool {
+	let mut onBoard = [false;52];
+	for card in board.cells.iter().chain(board.stacks.iter()).flatten() {
+		onBoard[cardIndex(card)] = true;
+	}
+
+	// edges[a] holds the cards that can't reach the goal until a has
+	let mut edges: Vec<Vec<usize>> = vec![Vec::new();52];
+	for i in 0..52 {
+		if onBoard[i] && i % 13 != 12 && onBoard[i+1] {
+			edges[i].push(i+1);
+		}
+	}
+	for stack in board.stacks.iter() {
+		for (start,length) in immovableRuns(board, stack) {
+			let top = stack[start + length - 1];
+			if top.value == 1 { continue }
+			let below = cardIndex(&top) - 1; // the card the goal needs before the run can start to come apart
+			if !onBoard[below] { continue }
+
+			// only the top cells-many cards of the run can ever go anywhere but the goal, so everything under those is held
+			let held = start + length - board.cells.len() - 1;
+			for card in stack[..held].iter() {
+				edges[below].push(cardIndex(card));
+			}
+		}
+	}
+
+	// Kahn's algorithm: if the cards can't all be put in an order that respects the edges, there's a cycle
+	let mut incoming = [0u32;52];
+	for targets in edges.iter() {
+		for t in targets { incoming[*t] += 1 }
+	}
+	let mut ready: Vec<usize> = (0..52).filter(|i| onBoard[*i] && incoming[*i] == 0).collect();
+	let mut ordered = 0;
+	while let Some(i) = ready.pop() {
+		ordered += 1;
+		for t in edges[i].iter() {
+			incoming[*t] -= 1;
+			if incoming[*t] == 0 { ready.push(*t) }
+		}
+	}
+	return ordered < onBoard.iter().filter(|b| **b).count();
+}
+
+impl Game {
+
+	fn noMovesWithFullCells(&self) -> bool {
+		if self.board.cells.iter().any(|c| c.is_empty()) {
+			return false;
+		}
+		return !moveSources().any(|source| self.findLegalMove(source).is_some());
+	}
+
+	// true if one of the detectors turned on in the options finds the current position can't be won
+	pub(crate) fn isDeadEnd(&self) -> bool {
+		let detectors = self.options.deadEnds;
+		return (detectors.cells && self.noMovesWithFullCells())
+			|| (detectors.buried && buriedRun(&self.board))
+			|| (detectors.circular && circularBlock(&self.board));
+	}
+}
@@ -0,0 +1,49 @@
+// Deal codes: a compact, copyable name for a fresh deal, so a deal found in a batch can be picked out and played again.
+// The code is the 52 cards in the order they are dealt ( each stack bottom to top, then the two occupied cells ), one byte per card
+// ( suit*13 + value-1 ), base64 encoded.
+
+use base64::prelude::*;
+
+use crate::{emptyBoard, Board, Card};
+use crate::validate::BoardError;
+
+const STACK_DEPTH: usize = 5; // cards dealt to each stack
+const DEALT_CELLS: usize = 2; // cells that start with a card in them
+
+impl Board {
+
+	// the deal code for this board, or None if it isn't a fresh deal
+	pub(crate) fn dealCode(&self) -> Option<String> {
+		let fresh = self.goals.iter().all(|g| g.is_empty())
+			&& self.stacks.iter().all(|s| s.len() == STACK_DEPTH)
+			&& self.cells.iter().enumerate().all(|(i,c)| c.len() == if i < DEALT_CELLS { 1 } else { 0 });
+		if !fresh {
+			return None;
+		}
+
+		let bytes: Vec<u8> = self.stacks.iter().chain(self.cells.iter())
+			.flatten()
+			.map(|c| c.suit*13 + c.value - 1)
+			.collect();
+		return Some(BASE64_URL_SAFE_NO_PAD.encode(bytes));
+	}
+
+	pub(crate) fn fromDealCode(code:&str) -> Result<Board,BoardError> {
+		let bytes = BASE64_URL_SAFE_NO_PAD.decode(code.trim()).map_err(|e| BoardError::Malformed(format!("bad deal code: {e}")))?;
+		if bytes.len() != 52 || bytes.iter().any(|b| *b >= 52) {
+			return Err(BoardError::Malformed("bad deal code: expected 52 cards".to_string()));
+		}
+
+		let mut cards = bytes.iter().map(|b| Card { suit: b / 13, value: b % 13 + 1 });
+		let mut board = emptyBoard();
+		for stack in board.stacks.iter_mut() {
+			stack.extend(cards.by_ref().take(STACK_DEPTH));
+		}
+		for cell in board.cells.iter_mut().take(DEALT_CELLS) {
+			cell.extend(cards.by_ref().take(1));
+		}
+
+		board.validate()?;
+		return Ok(board);
+	}
+}
@@ -0,0 +1,162 @@
+// A difficulty rating for a solved deal, built from how hard the solver had to work and what a solution to it looks like.
+//
+//   nodes          positions the solver expanded before finding the solution
+//   backtracks     moves it had to take back along the way
+//   shortestLength supermoves in the shortest solution found ( see below )
+//   forcedCells    moves in that solution that park a card in a free cell when nothing else would still win
+//
+// The solver stops at the first solution, which wanders: it parks cards it didn't need to and takes the long way round to positions
+// it could have reached sooner.  Finding the true minimum would take searching every line, so instead the solution is shortened:
+// from each position along it, every sequence of up to SHORTCUT_DEPTH legal moves is tried, and the one reaching furthest along the
+// solution for the moves it takes is played instead.  Passes repeat until nothing gets shorter, so shortestLength is an upper bound
+// on the minimum and usually well under what the solver found.
+//
+// A cell move is forced when every other move from its position that doesn't fill a cell either loses or can't be shown to win by
+// the hint solver within FORCED_BUDGET positions.  Cell moves with an alternative are the solver's choice, not the deal's demand.
+//
+// The score puts the search effort on a log scale, since it ranges over several orders of magnitude between deals, and adds the
+// shape of the solution on top: long solutions and ones that can't do without the cells are harder to find by hand.
+
+use std::collections::HashMap;
+
+use crate::{interrupt, Game, GameMove, Hint, LegalMove, StackType};
+use crate::error::SolverError;
+
+pub(crate) const EASY_BELOW: f64 = 42.5; // the bottom and top thirds of the 173 winnable deals among the 200 from seed 1
+pub(crate) const HARD_FROM: f64 = 54.0;
+
+const SHORTCUT_DEPTH: usize = 2; // moves tried from each position for a quicker way further along the solution
+const FORCED_BUDGET: u32 = 2000; // positions the hint solver may explore to show an alternative to a cell move still wins
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Difficulty {
+	pub(crate) nodes: u64,
+	pub(crate) backtracks: u64,
+	pub(crate) shortestLength: usize,
+	pub(crate) forcedCells: usize,
+	pub(crate) score: f64
+}
+
+impl Difficulty {
+
+	pub(crate) fn band(&self) -> &'static str {
+		if self.score < EASY_BELOW { return "easy" }
+		if self.score < HARD_FROM { return "medium" }
+		return "hard";
+	}
+}
+
+// a supermove as the single move that plays it again
+fn asLegalMove(moves:&[GameMove]) -> LegalMove {
+	// an extent move passes through the cells, so it is the last card move that lands on the real target
+	return LegalMove { source: moves[0].source, target: moves[moves.len()-1].target, extent: moves[0].extent };
+}
+
+fn isCellMove(legalMove:&LegalMove) -> bool {
+	return matches!(legalMove.target.stackType,StackType::CELL);
+}
+
+impl Game {
+
+	// Search every line of up to SHORTCUT_DEPTH moves from the current position for one reaching a position further along the
+	// solution, keeping in best the one that ends up furthest ahead for the moves it takes.  along maps each position on the solution
+	// to how far along it is.  Positions are matched exactly, by their text notation rather than the checksum, which takes stacks or
+	// cells in another order to be the same board: the solution's later moves name the particular stacks and cells they use
+	fn findShortcut(&mut self,along:&HashMap<String,usize>,line:&mut Vec<LegalMove>,best:&mut (usize,Vec<LegalMove>)) -> Result<(),SolverError> {
+		for legalMove in self.everyMove()? {
+			self.playMove(&legalMove)?;
+			line.push(legalMove);
+
+			if let Some(&reached) = along.get(&self.board.toText()) {
+				if reached as isize - line.len() as isize > best.0 as isize - best.1.len() as isize {
+					*best = (reached,line.clone());
+				}
+			}
+			if line.len() < SHORTCUT_DEPTH {
+				self.findShortcut(along, line, best)?;
+			}
+
+			line.pop();
+			self.undoSupermove()?;
+		}
+		return Ok(());
+	}
+
+	// One pass of cutting detours out of a solution played from the current position, which is left where it started
+	fn shortcutPass(&mut self,solution:&[LegalMove]) -> Result<Vec<LegalMove>,SolverError> {
+		let start = self.gameMoves.len();
+		let mut along: HashMap<String,usize> = HashMap::new();
+		along.insert(self.board.toText(), 0);
+		for (index,legalMove) in solution.iter().enumerate() {
+			self.playMove(legalMove)?;
+			along.insert(self.board.toText(), index + 1);
+		}
+		while self.gameMoves.len() > start {
+			self.undoSupermove()?;
+		}
+
+		let mut shortened = Vec::new();
+		let mut index = 0;
+		while index < solution.len() {
+			if interrupt::requested() { return Err(SolverError::Interrupted) }
+
+			let mut best = (index + 1,vec![solution[index].clone()]);
+			self.findShortcut(&along, &mut Vec::new(), &mut best)?;
+			for legalMove in best.1 {
+				self.playMove(&legalMove)?;
+				shortened.push(legalMove);
+			}
+			index = best.0;
+		}
+
+		while self.gameMoves.len() > start {
+			self.undoSupermove()?;
+		}
+		return Ok(shortened);
+	}
+
+	// the difficulty of the game just solved, measured from its search statistics and solution.  Only meaningful once solve() has
+	// succeeded.  Shortening the solution and judging its cell moves take a search of their own, which Ctrl-C interrupts
+	pub(crate) fn difficulty(&self) -> Result<Difficulty,SolverError> {
+		let mut walker = Game::fromBoard(self.startingBoard(), self.tally);
+		walker.render = false;
+		walker.options = self.options;
+
+		let mut solution: Vec<LegalMove> = self.supermoves().iter().map(|moves| asLegalMove(moves)).collect();
+		loop {
+			let shortened = walker.shortcutPass(&solution)?;
+			if shortened.len() >= solution.len() { break }
+			solution = shortened;
+		}
+
+		let mut forcedCells = 0;
+		for legalMove in solution.iter() {
+			if interrupt::requested() { return Err(SolverError::Interrupted) }
+
+			if isCellMove(legalMove) {
+				let mut alternativeWins = false;
+				for alternative in walker.everyMove()?.iter().filter(|m| !isCellMove(m)) {
+					walker.playMove(alternative)?;
+					alternativeWins = matches!(walker.hint(FORCED_BUDGET)?,Hint::Won | Hint::Move(_));
+					walker.undoSupermove()?;
+					if alternativeWins { break }
+				}
+				if !alternativeWins { forcedCells += 1 }
+			}
+			walker.playMove(legalMove)?;
+		}
+
+		let score = 8.0 * (1.0 + self.nodes as f64).log10()
+			+ 4.0 * (1.0 + self.backtracks as f64).log10()
+			+ solution.len() as f64 / 10.0
+			+ forcedCells as f64;
+
+		return Ok(Difficulty {
+			nodes: self.nodes,
+			backtracks: self.backtracks,
+			shortestLength: solution.len(),
+			forcedCells,
+			score
+		});
+	}
+}
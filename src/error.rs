@@ -0,0 +1,38 @@
+// Errors the solver can run into while playing out a game.  Apart from Interrupted these all indicate the game's state has gone
+// wrong somehow, and are returned rather than ending the process so whoever is driving the solver can decide what to do about it.
+
+use std::fmt;
+
+use crate::{cardName, positionName, Card, Position};
+use crate::validate::BoardError;
+
+#[derive(Clone, Debug)]
+pub(crate) enum SolverError {
+	InvalidBoard(BoardError), // the starting position failed validation
+	EmptySource(Position), // attempted to move a card off an empty stack
+	NothingToUndo, // the move stack is empty
+	NotEnoughCells { extent: u16, freeCells: u16 }, // an extent move needs extent-1 free cells
+	UnexpectedCard { position: Position, expected: Card }, // a recorded move doesn't match the card on the board
+	IllegalMove { card: Card, target: Position }, // a recorded move the rules don't allow
+	Interrupted // Ctrl-C was pressed while solving
+}
+
+impl fmt::Display for SolverError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			SolverError::InvalidBoard(e) => write!(f,"invalid board: {e}"),
+			SolverError::EmptySource(p) => write!(f,"no card to move from {0}",positionName(*p)),
+			SolverError::NothingToUndo => write!(f,"ran out of moves to undo"),
+			SolverError::NotEnoughCells { extent, freeCells } => write!(f,"cannot move {extent} cards with only {freeCells} free cells"),
+			SolverError::UnexpectedCard { position, expected } => write!(f,"expected {0} on top of {1}",cardName(Some(expected), "".to_string()).trim(),positionName(*position)),
+			SolverError::IllegalMove { card, target } => write!(f,"{0} cannot move to {1}",cardName(Some(card), "".to_string()).trim(),positionName(*target)),
+			SolverError::Interrupted => write!(f,"interrupted")
+		}
+	}
+}
+
+impl From<BoardError> for SolverError {
+	fn from(e: BoardError) -> Self {
+		return SolverError::InvalidBoard(e);
+	}
+}
@@ -0,0 +1,84 @@
+// Search seeds for deals that fall in a requested difficulty band ( or that are provably unsolvable ), to build practice sets.
+// Deals the solver abandons are skipped, since we can't say how hard they are.
+//
+//   seahaven generate --band easy|medium|hard|unsolvable [--min score] [--max score] [--count n] [--seed s] [--max-seeds m] [--out file]
+
+use std::io::Write;
+
+use crate::{dealBoard, optionValue, Game, Tally};
+use crate::difficulty::{EASY_BELOW, HARD_FROM};
+use crate::error::SolverError;
+use crate::options::SolverOptions;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Target {
+	Score { min: f64, max: f64 }, // winnable, with a difficulty score in min..max
+	Unsolvable
+}
+
+impl Target {
+
+	// --band picks a named band, and --min / --max narrow it or give a range of scores directly
+	pub(crate) fn fromArgs(args:&[String]) -> Option<Target> {
+		let (mut min,mut max) = match optionValue(args,"--band") {
+			Some("unsolvable") => return Some(Target::Unsolvable),
+			Some("easy") => (0.0,EASY_BELOW),
+			Some("medium") => (EASY_BELOW,HARD_FROM),
+			Some("hard") => (HARD_FROM,f64::MAX),
+			Some(_) => return None,
+			None => (0.0,f64::MAX)
+		};
+		if let Some(m) = optionValue(args,"--min").and_then(|m| m.parse::<f64>().ok()) { min = m }
+		if let Some(m) = optionValue(args,"--max").and_then(|m| m.parse::<f64>().ok()) { max = m }
+
+		return Some(Target::Score { min, max });
+	}
+}
+
+// Try seeds from startSeed upward until count matching deals are found or maxSeeds have been tried, writing one CSV line per match.
+// Returns how many were found
+pub(crate) fn generateDeals(target:Target,count:u32,startSeed:u64,maxSeeds:u64,options:SolverOptions,out:&mut dyn Write) -> std::io::Result<u32> {
+	let tally = Tally::default();
+	let mut found = 0;
+
+	writeln!(out,"seed,deal,outcome,difficulty,band")?;
+	for seed in startSeed..startSeed.saturating_add(maxSeeds) {
+		if found >= count { break }
+
+		let mut game = Game::fromBoard(dealBoard(seed), tally);
+		game.render = false;
+		game.options = options;
+		let dealCode = game.board.dealCode().unwrap_or_default();
+
+		let solved = game.solve().and_then(|success| {
+			let difficulty = if success { Some(game.difficulty()?) } else { None };
+			return Ok((success,difficulty));
+		});
+		let (success,difficulty) = match solved {
+			Ok(s) => s,
+			Err(SolverError::Interrupted) => {
+				eprintln!("Interrupted at seed {seed}");
+				break;
+			},
+			Err(e) => {
+				eprintln!("Seed {seed}: solver error: {e}");
+				continue;
+			}
+		};
+
+		let line = match (target,difficulty) {
+			(Target::Unsolvable,None) if !success && !game.abandoned => format!("{seed},{dealCode},loser,,"),
+			(Target::Score { min, max },Some(d)) => {
+				if d.score < min || d.score >= max { continue }
+				format!("{seed},{dealCode},winnable,{0:.1},{1}",d.score,d.band())
+			},
+			_ => continue
+		};
+
+		writeln!(out,"{line}")?;
+		out.flush()?;
+		found += 1;
+	}
+
+	return Ok(found);
+}
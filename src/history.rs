@@ -0,0 +1,150 @@
+// Move history for a game.  Every card move is kept in gameMoves, tagged with the supermove group it belongs to, so that
+// undo and redo can step through whole supermoves and a solution can be written out as a readable list of moves.
+
+use crate::{cardName, positionName, Game, GameMove, StackType};
+use crate::error::SolverError;
+
+impl GameMove {
+
+	// e.g. "Move 5H from Stack 3 to Cell 1"
+	pub(crate) fn describe(&self) -> String {
+		let name = cardName(Some(&self.card), "".to_string());
+		return format!("Move {0} from {1} to {2}",name.trim(),positionName(self.source),positionName(self.target));
+	}
+}
+
+// describe a whole supermove, given every card move in its group in the order they were played
+pub(crate) fn describeSupermove(moves:&[GameMove]) -> String {
+	if moves.len() == 1 {
+		return moves[0].describe();
+	}
+
+	// an extent move parks the top cards in the cells, moves the inner most card, then brings the rest back on top of it.
+	// The inner most card is the one that doesn't go to a cell
+	let inner = moves.iter().find(|m| !matches!(m.target.stackType,StackType::CELL)).unwrap_or(&moves[0]);
+	let name = cardName(Some(&inner.card), "".to_string());
+	let above = moves[0].extent-1;
+	let cards = if above == 1 { "card" } else { "cards" };
+	return format!("Move {0} and the {above} {cards} above it from {1} to {2}",name.trim(),positionName(inner.source),positionName(inner.target));
+}
+
+impl Game {
+
+	// take back the most recent supermove without keeping it for redo.  This is what the solver uses to backtrack
+	pub(crate) fn undoSupermove(&mut self) -> Result<(),SolverError> {
+		let group = self.gameMoves.last().ok_or(SolverError::NothingToUndo)?.group;
+		while self.gameMoves.last().is_some_and(|m| m.group == group) {
+			self.undoLastMove()?;
+		}
+		return Ok(());
+	}
+
+	// take back the most recent card move, keeping it so redoCard() can play it again
+	pub(crate) fn undoCard(&mut self) -> Result<(),SolverError> {
+		let gameMove = self.gameMoves.last().cloned().ok_or(SolverError::NothingToUndo)?;
+		self.undoLastMove()?;
+		self.redoMoves.push(gameMove);
+		return Ok(());
+	}
+
+	// play the most recently undone card move again.  Returns false if there is nothing to redo
+	pub(crate) fn redoCard(&mut self) -> Result<bool,SolverError> {
+		let gameMove = match self.redoMoves.pop() {
+			Some(m) => m,
+			None => return Ok(false)
+		};
+		self.group = gameMove.group;
+		self.moveCard(gameMove.source, gameMove.target, gameMove.extent)?;
+		return Ok(true);
+	}
+
+	// take back the most recent supermove as a single step, keeping it so redo() can play it again
+	pub(crate) fn undo(&mut self) -> Result<(),SolverError> {
+		let group = self.gameMoves.last().ok_or(SolverError::NothingToUndo)?.group;
+		while self.gameMoves.last().is_some_and(|m| m.group == group) {
+			self.undoCard()?;
+		}
+		return Ok(());
+	}
+
+	// play the most recently undone supermove again.  Returns false if there is nothing to redo
+	pub(crate) fn redo(&mut self) -> Result<bool,SolverError> {
+		let group = match self.redoMoves.last() {
+			Some(m) => m.group,
+			None => return Ok(false)
+		};
+		while self.redoMoves.last().is_some_and(|m| m.group == group) {
+			self.redoCard()?;
+		}
+		return Ok(true);
+	}
+
+	// undo every move, leaving the board at its starting position with the whole game available to redo
+	pub(crate) fn rewind(&mut self) -> Result<(),SolverError> {
+		while !self.gameMoves.is_empty() {
+			self.undo()?;
+		}
+		return Ok(());
+	}
+
+	// the game's moves so far as supermoves, each given as the card moves it was made of
+	pub(crate) fn supermoves(&self) -> Vec<&[GameMove]> {
+		return self.gameMoves.chunk_by(|a,b| a.group == b.group).collect();
+	}
+
+	// a readable list of the moves played so far, one line per supermove
+	pub(crate) fn describeHistory(&self) -> Vec<String> {
+		return self.supermoves().iter().map(|moves| describeSupermove(moves)).collect();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{dealBoard, LegalMove, Tally};
+
+	// the first deal, counting up from seed 1, that opens with a legal extent move, and that move
+	fn dealWithExtentMove() -> (Game,LegalMove) {
+		for seed in 1.. {
+			let mut game = Game::fromBoard(dealBoard(seed), Tally::default());
+			game.render = false;
+			let extentMove = game.everyMove().unwrap().into_iter()
+				.find(|m| m.extent > 1 && matches!(m.source.stackType,StackType::TABLEAU) && matches!(m.target.stackType,StackType::TABLEAU));
+			if let Some(m) = extentMove {
+				return (game,m);
+			}
+		}
+		unreachable!();
+	}
+
+	#[test]
+	fn undoAndRedoAWholeExtentMove() {
+		let (mut game,extentMove) = dealWithExtentMove();
+		let before = game.board.toText();
+		game.playMove(&extentMove).unwrap();
+		let after = game.board.toText();
+		assert_eq!(game.gameMoves.len(), (extentMove.extent as usize - 1) * 2 + 1);
+		assert_eq!(game.supermoves().len(), 1);
+
+		game.undo().unwrap();
+		assert!(game.gameMoves.is_empty());
+		assert_eq!(game.board.toText(), before);
+
+		assert!(game.redo().unwrap());
+		assert_eq!(game.board.toText(), after);
+		assert!(!game.redo().unwrap());
+	}
+
+	#[test]
+	fn playingAMoveClearsRedo() {
+		let (mut game,extentMove) = dealWithExtentMove();
+		game.playMove(&extentMove).unwrap();
+		game.undo().unwrap();
+		assert!(!game.redoMoves.is_empty());
+
+		let other = game.everyMove().unwrap().into_iter().find(|m| m.extent == 1).unwrap();
+		game.playMove(&other).unwrap();
+		assert!(game.redoMoves.is_empty());
+		assert!(!game.redo().unwrap());
+	}
+}
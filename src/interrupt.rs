@@ -0,0 +1,34 @@
+// Ctrl-C handling.  Rather than killing the process part way through drawing a board, Ctrl-C sets a flag that the solver checks
+// before expanding each position, so the search unwinds with SolverError::Interrupted and whoever is driving it can tidy up the
+// terminal and save what it has.  Loops that run many solves or don't use the solver at all ( verify-keys, verify-pruning ) check
+// the flag themselves.  A second Ctrl-C, for when that isn't happening fast enough, exits straight away.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use terminal::Action;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn install() {
+	let result = ctrlc::set_handler(|| {
+		if REQUESTED.swap(true, Ordering::SeqCst) {
+			restoreTerminal();
+			std::process::exit(130);
+		}
+	});
+	if let Err(e) = result {
+		eprintln!("Unable to handle Ctrl-C: {e}");
+	}
+}
+
+pub(crate) fn requested() -> bool {
+	return REQUESTED.load(Ordering::SeqCst);
+}
+
+// put back the colors and cursor the board drawing may have changed
+pub(crate) fn restoreTerminal() {
+	let term = terminal::stdout();
+	if let Err(e) = term.act(Action::ResetColor).and_then(|_| term.act(Action::ShowCursor)) {
+		eprintln!("Unable to restore the terminal: {e}");
+	}
+}
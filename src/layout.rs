@@ -0,0 +1,107 @@
+// Where everything goes when a game is drawn: title, goals and cells along the top, the tableau below them, and the stats panel.
+// The layout adapts to the terminal size.  The stats panel moves under the tableau when the terminal is too narrow to have it
+// alongside, columns are packed tighter when even the tableau doesn't fit, and when the tableau is taller than the space left
+// for it only its bottom rows ( where the play is ) are shown, with a marker for how many are scrolled off the top.
+
+use terminal::{Color, Retrieved, Value};
+
+use crate::Game;
+
+const STATS_X: u16 = 50; // where the stats panel starts when it sits beside the tableau
+const STATS_WIDTH: u16 = 48;
+const MESSAGE_ROWS: u16 = 3; // left free below the board for printMessageLine
+const PADDING_ROWS: usize = 10; // blank rows drawn below the longest stack, to erase cards left over from a longer stack
+
+// a run of text to draw at a terminal position, in a given color
+pub(crate) struct Glyph {
+	pub(crate) x: u16,
+	pub(crate) y: u16,
+	pub(crate) color: Color,
+	pub(crate) text: String
+}
+
+// the terminal's size in columns and rows, or a generous default if it can't be determined
+pub(crate) fn terminalSize() -> (u16,u16) {
+	match terminal::stdout().get(Value::TerminalSize) {
+		Ok(Retrieved::TerminalSize(columns,rows)) if columns > 0 && rows > 0 => (columns,rows),
+		_ => (100,60)
+	}
+}
+
+impl Game {
+
+	// Lay out the board and stats panel as text at terminal positions, for a terminal of the given size in columns and rows.
+	// This is what print() draws, and what the exporters render from
+	pub(crate) fn layout(&self,title: &str,size: (u16,u16)) -> Vec<Glyph> {
+		let (width,height) = size;
+		let mut glyphs: Vec<Glyph> = Vec::new();
+		let mut put = |x:u16,y:u16,color:Color,text:String| glyphs.push(Glyph { x, y, color, text });
+
+		put(1,1,Color::Reset,title.to_string());
+
+		let offsetY = 2;
+		let tableauY = offsetY+3;
+		let columns = self.board.stacks.len() as u16;
+
+		// a column is normally 4 wide, leaving a space after 10H.  In a narrow terminal drop the space
+		let columnWidth = if width > 1 + columns*4 { 4 } else { 3 };
+		let cellsX = if columnWidth == 4 { 30 } else { 1 + 4*columnWidth + 2 };
+
+		let stats = self.dashboardLines();
+		let statsBeside = width >= STATS_X + STATS_WIDTH;
+		let statsRows = if statsBeside { 0 } else { stats.len() as u16 + 1 };
+
+		// print goals
+		for (i,goalStack) in self.board.goals.iter().enumerate() {
+			put(1+(i as u16 * columnWidth),offsetY+1,self.appearance.color(goalStack.last()),self.appearance.label(goalStack.last()," - ".to_string()));
+		}
+
+		// print cells
+		for (i,cellStack) in self.board.cells.iter().enumerate() {
+			put(cellsX+(i as u16 * columnWidth),offsetY+1,self.appearance.color(cellStack.last()),self.appearance.label(cellStack.last()," x ".to_string()));
+		}
+
+		// work out how many tableau rows there is room for, and scroll if the longest stack doesn't fit
+		let longest = self.board.stacks.iter().map(|s| s.len()).max().unwrap();
+		let available = height.saturating_sub(tableauY + statsRows + MESSAGE_ROWS).max(2) as usize;
+		let rowsDrawn = (longest + PADDING_ROWS).min(available);
+		let mut firstRow = 0;
+		let mut drawY = tableauY;
+		if longest > available {
+			firstRow = longest - (available - 1); // keep the top cards of every stack in view
+			put(1,drawY,Color::Reset,format!("^ {firstRow} more rows"));
+			drawY += 1;
+		}
+
+		for row in firstRow..(firstRow + rowsDrawn - (drawY - tableauY) as usize) {
+			for (col,tableStack) in self.board.stacks.iter().enumerate() {
+				let card = tableStack.get(row);
+				let mut name = self.appearance.label(card,"   ".to_string());
+				if columnWidth < 4 { name = format!("{0:<3}",name.trim_end()) }
+				put(1+((col as u16)*columnWidth),drawY,self.appearance.color(card),name);
+			}
+			drawY += 1;
+		}
+
+		if statsBeside {
+			// padded out to the panel width so a line that gets shorter doesn't leave the end of the old one behind
+			for (i,line) in stats.into_iter().enumerate() {
+				put(STATS_X,offsetY+2+i as u16,Color::Reset,format!("{line:<0$}",STATS_WIDTH as usize));
+			}
+		} else {
+			// anchored to the rows just above the message area, so they stay put as the tableau grows and shrinks
+			let statsY = (tableauY + available as u16 + 1).max(drawY + 1);
+			for (i,line) in stats.into_iter().enumerate() {
+				let line: String = format!("{line:<0$}",width as usize).chars().take(width.saturating_sub(1) as usize).collect();
+				put(1,statsY+i as u16,Color::Reset,line);
+			}
+		}
+
+		return glyphs;
+	}
+
+	// the first row below everything layout() draws, where messages can go
+	pub(crate) fn messageRow(&self,size: (u16,u16)) -> u16 {
+		return self.layout("",size).iter().map(|g| g.y + 1).max().unwrap_or(1);
+	}
+}
@@ -0,0 +1,201 @@
+// Counting a deal's winning lines, to tell a deal with one narrow way through from one that can be won almost any way.  Where the
+// solver stops at the first win, this carries on through every position the solver's moves can reach and counts the distinct wins.
+// These are solver lines, made only of the moves the solver would try ( see findLegalMove ), and are reported as such.  Counting
+// over every legal move instead ( see critical.rs ) finds no win at all within the default budget on the deals from seeds 1 and 2,
+// lost among lines that park cards in the cells for no reason, so it can't give a count worth having.
+//
+//   seahaven lines <file> [--limit n] [--budget n]      ( plus any solver options )
+//
+// Lines that play the same moves in a different order are transpositions of each other and count once, so a deal isn't called open
+// just because the last few cards can go to the goals in any order.  A move is identified by the card moved ( the bottom card of a
+// run ) and where it goes: a goal, a cell, an empty column or onto the card above it in its suit, the only card it can go onto.  A
+// line is then the collection of its moves, kept as the sum of a random number for each move so lines from a position can be built
+// from the lines of the positions after it.  Each position's lines are worked out once and reused wherever it is reached again.
+//
+// Counting stops at --limit lines ( default 100 ), reported as "at least".  --budget ( default the abandon threshold ) caps the
+// positions explored, after which the count is only what was found so far.  A line never passes through the same position twice, so
+// positions already on the line being explored are skipped.  The lines from a position that had to skip one further up the line
+// are missing the lines through it, so they are only reused while that position is on the line again.
+//
+// Along with the count comes how forced the deal is: following the first winning line, how many of its positions have only one move
+// that still wins.  Unlike the count, here every legal move is judged, not just the ones the solver tries, since a player can make
+// any of them, and a move the solver wouldn't try is shown to win by finding one solver line from where it leads.  That needs every
+// line counted, so it is only given for deals with fewer lines than the limit, which are the narrow ones it matters for.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::{interrupt, isSuccess, Game, LegalMove, StackType};
+use crate::error::SolverError;
+
+pub(crate) const DEFAULT_LIMIT: usize = 100;
+
+pub(crate) struct LineCount {
+	pub(crate) lines: usize, // distinct winning solver lines found, up to the limit
+	pub(crate) atLeast: bool, // the limit was reached, so there may be more
+	pub(crate) complete: bool, // every reachable position was explored within the budget
+	pub(crate) positions: usize, // positions explored
+	pub(crate) forced: Option<(usize,usize)> // the moves in the first winning line and how many had only one winning move, if worked out
+}
+
+// the lines from a position, as sums of move hashes
+#[derive(Clone)]
+struct Known {
+	lines: HashSet<u64>,
+	skipped: Vec<String> // positions further up the line that were skipped, so the lines through them are missing
+}
+
+struct Counter {
+	moveHashes: Vec<u64>, // the random number for each card and kind of move
+	memo: HashMap<String,Known>, // the lines from each position explored
+	onLine: HashMap<String,usize>, // positions on the line currently being explored, by how far along it they are
+	explored: usize,
+	limit: usize,
+	budget: usize,
+	abandoned: bool
+}
+
+impl Game {
+
+	// the random number identifying a move, by the card moved and the kind of place it goes to
+	fn moveHash(&self,legalMove:&LegalMove,moveHashes:&[u64]) -> u64 {
+		let source = self.resolvePosition(legalMove.source);
+		let card = source[source.len() - (legalMove.extent.max(1) as usize).min(source.len())];
+		let kind = match legalMove.target.stackType {
+			StackType::GOAL => 0,
+			StackType::CELL => 1,
+			StackType::TABLEAU if self.resolvePosition(legalMove.target).is_empty() => 2,
+			StackType::TABLEAU => 3
+		};
+		return moveHashes[(card.suit as usize * 13 + card.value as usize - 1) * 4 + kind];
+	}
+
+	// the lines from the current position, which is neither won nor already being explored and is depth moves along the line
+	fn linesFrom(&mut self,counter:&mut Counter,depth:usize) -> Result<Known,SolverError> {
+		if interrupt::requested() { return Err(SolverError::Interrupted) }
+		self.nodes += 1;
+		counter.explored += 1;
+
+		let key = self.checksum();
+		counter.onLine.insert(key.clone(), depth);
+		let mut lines: HashSet<u64> = HashSet::new();
+		let mut skipped: Vec<String> = Vec::new();
+
+		for legalMove in self.orderedMoves() {
+			if lines.len() >= counter.limit || counter.abandoned { break }
+			let hash = self.moveHash(&legalMove, &counter.moveHashes);
+			self.playMove(&legalMove)?;
+
+			if isSuccess(&self.board) {
+				lines.insert(hash);
+			} else {
+				let next = self.checksum();
+				let after = match counter.memo.get(&next) {
+					_ if counter.onLine.contains_key(&next) => {
+						skipped.push(next);
+						None
+					},
+					// only missing lines through positions that are on this line too, so nothing this line could use
+					Some(known) if known.skipped.iter().all(|p| counter.onLine.contains_key(p)) => Some(known.clone()),
+					_ if self.isDeadEnd() => {
+						self.deadEnds += 1;
+						None
+					},
+					_ if counter.explored >= counter.budget => {
+						counter.abandoned = true;
+						None
+					},
+					_ => Some(self.linesFrom(counter, depth + 1)?)
+				};
+				if let Some(known) = after {
+					skipped.extend(known.skipped);
+					for line in known.lines {
+						if lines.len() >= counter.limit { break }
+						lines.insert(line.wrapping_add(hash));
+					}
+				}
+			}
+
+			self.undoSupermove()?;
+		}
+
+		// skipping this position or one after it only leaves out lines that go round in a circle, but skipping one before it leaves
+		// out lines this position would have if reached another way
+		skipped.retain(|p| counter.onLine[p] < depth);
+		skipped.sort();
+		skipped.dedup();
+		counter.onLine.remove(&key);
+		let known = Known { lines, skipped };
+		if !counter.abandoned { // an abandoned count is missing lines, so isn't kept for other positions to use
+			counter.memo.insert(key, known.clone());
+		}
+		return Ok(known);
+	}
+
+	// Follow the first winning line from the current position, returning its length and how many of its positions had only one
+	// winning move, or None if the budget ran out first.  The board is left where it started
+	fn forcedMoves(&mut self,counter:&mut Counter) -> Result<Option<(usize,usize)>,SolverError> {
+		let start = self.gameMoves.len();
+		let mut length = 0;
+		let mut forced = 0;
+		let mut visited: HashSet<String> = HashSet::new();
+		counter.limit = 1; // a move the solver wouldn't try may lead somewhere not yet counted, and one line shows it still wins
+
+		while !isSuccess(&self.board) && !counter.abandoned {
+			visited.insert(self.checksum());
+			let mut winning: Vec<LegalMove> = Vec::new();
+			for legalMove in self.everyMove()? {
+				self.playMove(&legalMove)?;
+				let key = self.checksum();
+				let wins = if isSuccess(&self.board) { true }
+					else if visited.contains(&key) { false }
+					else if let Some(known) = counter.memo.get(&key).filter(|k| k.skipped.is_empty()) { !known.lines.is_empty() }
+					else if self.isDeadEnd() { false }
+					else { !self.linesFrom(counter, 0)?.lines.is_empty() };
+				self.undoSupermove()?;
+				if wins { winning.push(legalMove) }
+			}
+			if winning.is_empty() { break }
+			if winning.len() == 1 { forced += 1 }
+			self.playMove(&winning[0])?;
+			length += 1;
+		}
+
+		while self.gameMoves.len() > start {
+			self.undoSupermove()?;
+		}
+		if counter.abandoned { return Ok(None) }
+		return Ok(Some((length,forced)));
+	}
+
+	// Count the distinct winning lines from the current position, up to limit, exploring at most budget positions
+	pub(crate) fn countLines(&mut self,limit:usize,budget:usize) -> Result<LineCount,SolverError> {
+		self.board.validate()?;
+		self.ordering = self.options.ordering.build();
+
+		let mut rng = StdRng::seed_from_u64(1);
+		let mut counter = Counter {
+			moveHashes: (0..52*4).map(|_| rng.gen()).collect(),
+			memo: HashMap::new(),
+			onLine: HashMap::new(),
+			explored: 0,
+			limit: limit.max(1),
+			budget,
+			abandoned: false
+		};
+
+		if isSuccess(&self.board) {
+			return Ok(LineCount { lines: 1, atLeast: false, complete: true, positions: 0, forced: Some((0,0)) });
+		}
+		let lines = self.linesFrom(&mut counter, 0)?.lines.len();
+		let atLeast = lines >= counter.limit;
+		let complete = !counter.abandoned;
+		let positions = counter.explored;
+		// positions left unexplored once the limit or budget was reached would look lost, so the walk only makes sense without them
+		let forced = if lines > 0 && !atLeast && complete { self.forcedMoves(&mut counter)? } else { None };
+
+		return Ok(LineCount { lines, atLeast, complete, positions, forced });
+	}
+}
@@ -1,17 +1,55 @@
 #![allow(non_snake_case)]
+#![allow(clippy::needless_return)] // functions end with an explicit return throughout
 
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use array_init;
-use std::{collections::HashMap,thread,time,process};
+use rand::{thread_rng, Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::{collections::HashMap,time,process};
+use std::io::Write;
 use terminal::{Clear, Action, Color};
-use base64::prelude::*;
-use sha2::{Sha256, Digest};
+
+mod appearance;
+mod canonical;
+mod cast;
+mod checkpoint;
+mod critical;
+mod dashboard;
+mod deal;
+mod deadend;
+mod difficulty;
+mod error;
+mod generate;
+mod history;
+mod interrupt;
+mod layout;
+mod lines;
+mod montecarlo;
+mod notation;
+mod options;
+mod ordering;
+mod pruning;
+mod replay;
+mod results;
+mod solution;
+mod svg;
+mod symmetry;
+mod tournament;
+mod validate;
+
+use appearance::Appearance;
+use checkpoint::Checkpoint;
+use error::SolverError;
+use results::{fingerprint, Outcome, ResultsStore};
+use difficulty::Difficulty;
+use options::SolverOptions;
+use ordering::MoveOrdering;
+use layout::terminalSize;
 
 const ABANDON_THRESHOLD:u32 = 500000;
+const HINT_BUDGET:u32 = 50000; // unique boards the hint solver may explore before giving up
 
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct Card {
 	suit: u8,
 	value: u8,
@@ -19,14 +57,14 @@ struct Card {
 
 type Stack = Vec<Card>;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Board {
 	goals: [Stack;4],
 	cells: [Stack;4],
 	stacks: [Stack;10]
 }
 
-#[derive(Copy,Clone)]
+#[derive(Copy,Clone,Default)]
 struct Tally {
 	totalGames: u32,
 	winnable: u32,
@@ -34,14 +72,18 @@ struct Tally {
 	abandoned: u32
 }
 
+// a single card moved from one stack to another.  A supermove ( an extent moved through the free cells ) is made up of several of these,
+// all sharing the same group
 #[derive( Clone, Debug)]
 struct GameMove {
 	source: Position,
 	target: Position,
-	msg: String,
-	extent: u16
+	card: Card,
+	extent: u16,
+	group: u32 // which supermove this card move belongs to, numbered from 0 in the order they were played
 }
 #[derive(Copy, Clone, Debug)]
+#[allow(clippy::upper_case_acronyms)] // the kinds of stack read as constants, e.g. StackType::GOAL
 enum StackType {
 	GOAL,
 	CELL,
@@ -62,7 +104,14 @@ struct LegalMove {
 	extent: u16
 }
 
-
+// the result of asking the solver what to do next from the current position
+#[derive( Clone, Debug)]
+enum Hint {
+	Move(LegalMove), // first move of a solution the solver found
+	Won, // the position is already solved, nothing left to do
+	Unknown, // no solution found within the budget.  The position may or may not be winnable
+	Lost // every line from this position was exhausted, it cannot be won
+}
 
 
 fn suitName(suit:u8) -> &'static str {
@@ -75,7 +124,21 @@ fn suitName(suit:u8) -> &'static str {
 	}
 }
 
+fn emptyBoard() -> Board {
+	let goals:[Stack;4] = array_init::array_init(|_| Stack::with_capacity(13));
+	let cells:[Stack;4] = array_init::array_init(|_| Stack::with_capacity(1));
+	let stacks:[Stack;10] = array_init::array_init(|_| Stack::with_capacity(52));
+
+	return Board { goals,cells,stacks };
+}
+
+// deal a random board
 fn initializeBoard() -> Board {
+	return dealBoard(thread_rng().gen());
+}
+
+// deal the board for a given seed.  The same seed always produces the same deal
+fn dealBoard(seed:u64) -> Board {
 	let mut deck:Stack = Stack::with_capacity(52);
 
 	// initialize deck sequentially
@@ -87,16 +150,12 @@ fn initializeBoard() -> Board {
 	}
 
 	// shuffle it
-	let mut rng: rand::prelude::ThreadRng = thread_rng();
+	let mut rng = StdRng::seed_from_u64(seed);
 	deck.shuffle(&mut rng);
 
 	// print!("{:?}",deck);
 	
-	let goals:[Stack;4] = array_init::array_init(|_| Stack::with_capacity(13));
-	let cells:[Stack;4] = array_init::array_init(|_| Stack::with_capacity(1));
-	let stacks:[Stack;10] = array_init::array_init(|_| Stack::with_capacity(52));
-
-	let mut board: Board = Board { goals,cells,stacks};
+	let mut board: Board = emptyBoard();
 
 	// init each of the 10 stacks with 5 cards each
 	for stack in board.stacks.iter_mut() {
@@ -114,6 +173,16 @@ fn initializeBoard() -> Board {
 }
 
 
+// human readable name of a position, e.g. "Stack 3" or "Cell 1"
+fn positionName(position:Position) -> String {
+	let kind = match position.stackType {
+		StackType::GOAL => "Goal",
+		StackType::CELL => "Cell",
+		StackType::TABLEAU => "Stack"
+	};
+	return format!("{kind} {0}",position.stackIndex+1);
+}
+
 fn colorCard(card: Option<&Card>) -> Color {
 
 	match card {
@@ -152,64 +221,31 @@ fn cardName(card: Option<&Card>,default:String) -> String {
 
 }
 
-fn cardNumerical(card: Option<&Card>) -> u16 {
-	match card {
-		Some(c) => c.suit as u16 * 100 + c.value as u16,
-		None => 0
-	}
-}
 
-
-// you cannot create a sequence of more than 5 consecutive cards if a lower card of the same suit is higher in the stack.
-// Doing so will block that suit from ever making it to the goal, because you can only move 5 cards in sequence at once
-// e.g. with stack 2H 10H 9H 8H 7H 6H, moving the 5H on the end would cause a situation where the 2H could never be freed.
+// you cannot create a sequence of more cards than can be moved at once ( maxExtent, free cells+1 with every cell free ) if a lower
+// card of the same suit is further down the stack.  Doing so will block that suit from ever making it to the goal: the sequence can
+// never be moved as a whole, and can only be taken apart from the top into the goal, which needs that lower card first.
+// e.g. with 4 cells and stack 2H 10H 9H 8H 7H 6H, moving the 5H on the end would cause a situation where the 2H could never be freed.
 // we can ensure this doesn't happen and reduce our possiblity tree
-fn isBlockingMove(card:&Card,targetStack:&Stack,extentLength:u16) -> bool {
+fn isBlockingMove(card:&Card,targetStack:&Stack,extentLength:u16,maxExtent:u16) -> bool {
 
-	if targetStack.len() < 5 {
+	if targetStack.len() as u16 + extentLength <= maxExtent {
 		return false;
 	} 
 
-	let mut foundLower = false;
-	let mut sequenceBroken = false;
-	let mut count = 1;
-
-	// println!("Stack {:?}",target.stack);
-	
-	for (i,stackCard) in targetStack[1..].iter().rev().enumerate() {
-		let pos = (targetStack.len() - i) - 1;
-		let nextCard = targetStack[pos-1];
-
-		// keep counting the sequence until its broken
-		if !sequenceBroken && stackCard.suit == nextCard.suit && stackCard.value == nextCard.value-1 {
-			count += 1;
-		} else {
-			sequenceBroken = true
-		}
-
-		if stackCard.suit == card.suit && stackCard.value < card.value {
-			foundLower = true;
-			break; // no reason to continue at this point
-		}
-		
-		// println!("item {0} {1:?} {2:?}",i,stackCard,nextCard);
-	}
-
-	// if we found a lower card higher in the stack AND the counted sequence + extentLength ( how many cards we are moving onto the stack ) >= 5 , then its a blocking move, as it will
-	// result in 6 or more cards in sequence with a lower card higher in the stack
-	if foundLower && (count + extentLength) >= 5 {
-		
-		return true;
+	// count the sequence on top of the target that the moved cards will extend
+	let count = stackOrderedCount(targetStack);
+	if count + extentLength <= maxExtent {
+		return false;
 	}
 
-	return false
+	let below = &targetStack[..targetStack.len() - count as usize];
+	return below.iter().any(|stackCard| stackCard.suit == card.suit && stackCard.value < card.value);
 }	
 
-
-
 // returns how many cards on the top of the stack are ordered ( inclusive ).  That is, there will always be at least one, unless the stack is empty
 fn stackOrderedCount(stack:&Stack) -> u16 {
-	if stack.len() == 0 {
+	if stack.is_empty() {
 		return 0;
 	}
 	let mut count = 1;
@@ -241,41 +277,12 @@ fn findExtent(board: &Board,stack: &Stack) -> u16 {
 	return 0
 
 }
-		
-
-
-
-// determines if the stack's top card suit has other cards in the same suit that disconnected in the stack. 
-fn isDisconnectedStack(stack:&Stack) -> bool {
-	if stack.len() <=1 {
-		return false;
-	}
-	// println!("isDisconnected {:?}",stack);
-
-	let mut breakPoint = false;
-	let mut disconnected = false;
-	let firstCard = stack.last().unwrap();
-
-	for (i,stackCard) in stack.iter().rev().enumerate() {
-		if stackCard.suit == firstCard.suit  {
-			if !breakPoint {
-				continue ;
-			}
-			disconnected = true;
-			break;		
-		}
-		breakPoint = true;
-	}
-
-	return disconnected;
-
-}
 
 // return a collection of all cell positions that have nothing in them
 fn findFreeCells(board: &Board) -> Vec<Position>  {
 	let mut freeCells: Vec<Position> = Vec::new();
 	for (stackIndex,stack) in board.cells.iter().enumerate() {
-		if stack.len() == 0 {
+		if stack.is_empty() {
 			freeCells.push(Position { 
 				stackIndex, 
 				stackType: StackType::CELL});
@@ -287,6 +294,12 @@ fn findFreeCells(board: &Board) -> Vec<Position>  {
 	
 }
 
+// every place a card can be moved from: the 4 cells, then the 10 tableau stacks
+fn moveSources() -> impl Iterator<Item = Position> {
+	return (0..4).map(|i| Position { stackIndex: i, stackType: StackType::CELL })
+		.chain((0..10).map(|i| Position { stackIndex: i, stackType: StackType::TABLEAU }));
+}
+
 // count how many free cells there are
 fn countFreeCells(board: &Board) -> u16  {
 	let count = findFreeCells(board).len() as u16;
@@ -303,132 +316,80 @@ fn isSuccess(board:&Board) -> bool {
 
 // create a unique checksum for the boards current state. This is used to ensure we never repeat a configuration, as its
 // easy in this game to achieve the same configuration from multiple move possibilities
-// Goal configuration is not considered
-// Cells are sorted to ensure that any order of the same cards in the cells are considered to be the same configuration
-// Stacks are sorted by the bottom most card, again to remove consideration of order from the checksum
+// Cells and stacks are sorted so that any order of the same cards in the cells, or of the same stacks, are considered to be the
+// same configuration.  See canonical.rs for how the key is built, and symmetry.rs for a key that also ignores which suit is which
 fn checksumBoard(board:&Board) -> String {
-	
-	// get a sorted group of cells
-	let mut cells: Vec<u16> = board.cells.iter()
-		.map(|s| cardNumerical(s.last()))
-		.collect::<Vec<u16>>();
-	cells.sort();
-
-	let mut stacks : Vec<Vec<u16>> = board.stacks.iter()
-		.map(|s| s.iter().map(|c| cardNumerical(Some(c))).collect::<Vec<u16>>()
-		).collect();
-	stacks.sort_by_key(|k| if k.len() == 0 { 0 } else { k[0] });
-	
-	
-	let key = format!("{0:?}{1:?}",cells,stacks);
-	return key;
-}
-
-
-
-
-
-// Check to see if the stack is fully ordered
-// a stack is considered to be fully ordered if any ordered sequence from the top of the stack down is made up of more than the available free cells + 1
-// ( once you've hit 6 cards, the only place you can move the top card is to the goal.  You'll fill up the available cells trying to move the whole sequence)
-fn isFullyOrdered(board:&Board,stack:&Stack) -> bool {
-	if stack.len() == 0 {
-		return true
-	}
-	let freeCells = countFreeCells(board);
-
-	if !(stack.len() as u16 > (freeCells + 1) ) { // impossible to be fully ordered unless stack size is greater than the available free cells + 1
-		return false;
-		}
-
-	let count = stackOrderedCount(stack);
-
-	if count > (freeCells+1) {
-		return true;
-	}
-
-	return false
+	return canonical::stateKey(board);
 }
 
-
 struct Game {
 	board: Board,
 	boardSet: HashMap<String,bool>,
 	stackSize: u32,
+	maxStackSize: u32, // deepest the search has gone
+	nodes: u64, // positions the solver has expanded, i.e. calls to cycleThroughCards
+	backtracks: u64, // moves the solver has had to take back
+	bestGoal: u16, // most cards in the goals at any point so far
+	startTime: time::Instant,
 	totalMoves: u32,
 	repeatsAvoided: u32,
+	deadEnds: u64, // positions the dead end detectors pruned
 	tally: Tally,
 	gameMoves: Vec<GameMove>,
-	abandoned: bool
+	redoMoves: Vec<GameMove>, // moves taken back by undo(), most recently undone last
+	group: u32, // supermove group assigned to the moves currently being made
+	abandoned: bool,
+	abandonThreshold: u32, // number of unique boards to explore before abandoning the game
+	render: bool, // whether to draw the board as the solver plays
+	appearance: Appearance, // card style and color theme used when drawing
+	options: SolverOptions, // how the search is run
+	ordering: Box<dyn MoveOrdering> // built from options.ordering when a solve starts
 }
 
 impl Game {
 	fn new(tally: Tally) -> Self {
 		let board = initializeBoard();
-	
+		return Game::fromBoard(board, tally);
+	}
+
+	fn fromBoard(board: Board,tally: Tally) -> Self {
 		Game {
 			board,
 			boardSet: HashMap::new(),
 			stackSize: 0,
+			maxStackSize: 0,
+			nodes: 0,
+			backtracks: 0,
+			bestGoal: 0,
+			startTime: time::Instant::now(),
 			totalMoves: 0,
 			repeatsAvoided: 0,
+			deadEnds: 0,
 			tally,
 			gameMoves: Vec::new(),
-			abandoned: false
+			redoMoves: Vec::new(),
+			group: 0,
+			abandoned: false,
+			abandonThreshold: ABANDON_THRESHOLD,
+			render: true,
+			appearance: Appearance::default(),
+			options: SolverOptions::default(),
+			ordering: SolverOptions::default().ordering.build()
 		}
 	}
 
+	// Drawing is best effort: a terminal that can't move the cursor or change color still gets the text, and a failure shouldn't
+	// stop a solve part way through, so the results of the terminal actions here and in printMessageLine are ignored
 	fn print(&self,title: &str) {
 		let term = terminal::stdout();
 	
-		term.act(Action::MoveCursorTo(1,1));
-		term.act(Action::SetForegroundColor(Color::Reset));
-		print!("{}",title);
-	
-		let offsetY = 2;
-	
-		// print goals
-		for (i,goalStack) in self.board.goals.iter().enumerate() {
-			term.act(Action::MoveCursorTo(1+(i as u16 * 4),offsetY+1));
-			term.act(Action::SetForegroundColor(colorCard(goalStack.last())));
-			let name = cardName(goalStack.last()," - ".to_string());
-			print!("{name}");
+		for glyph in self.layout(title,terminalSize()) {
+			let _ = term.act(Action::MoveCursorTo(glyph.x,glyph.y));
+			let _ = term.act(Action::SetForegroundColor(glyph.color));
+			print!("{0}",glyph.text);
 		}
-		
-		// print cells
-		for (i,cellStack) in self.board.cells.iter().enumerate() {
-			term.act(Action::MoveCursorTo(30+(i as u16 * 4),offsetY+1));
-			term.act(Action::SetForegroundColor(colorCard(cellStack.last())));
-			let name = cardName(cellStack.last()," x ".to_string());
-			print!("{name}");
-		}
-		// find the max length of the stacks
-		let maxLength = self.board.stacks.iter().map(|s| s.len()).max().unwrap()+10;
-	
-		for row in 0..maxLength {		
-			for (col,tableStack) in self.board.stacks.iter().enumerate() {
-				term.act(Action::MoveCursorTo(1+((col as u16)*4),offsetY+3+(row as u16)));			
-				let card = tableStack.get(row);
-				term.act(Action::SetForegroundColor(colorCard(card)));
-				let name = cardName(card,"   ".to_string());
-				print!("{name}");
-			}
-		}
-	
-		term.act(Action::SetForegroundColor(Color::Reset));
-	
-		term.act(Action::MoveCursorTo(50,offsetY+2));
-		print!("Games Played {0}",self.tally.totalGames);
-		term.act(Action::MoveCursorTo(50,offsetY+4));
-		print!("Winnable {0}  Losers: {1}  Abandoned {2}",self.tally.winnable,self.tally.losers,self.tally.abandoned);
-		term.act(Action::MoveCursorTo(50,offsetY+6));
-		print!("Stack Size {0}",self.stackSize);
-		term.act(Action::MoveCursorTo(50,offsetY+8));
-		print!("Total Moves {0}",self.totalMoves);
-		term.act(Action::MoveCursorTo(50,offsetY+10));
-		print!("Unique Boards {0}  Collisions: {1}",self.boardSet.len(),self.repeatsAvoided);
-		
-	
+
+		let _ = term.act(Action::SetForegroundColor(Color::Reset));
 	}	
 
 	// Resolve a position into a reference to a particlar card stack
@@ -454,9 +415,9 @@ impl Game {
 		return stack;
 	}
 
-	fn popCard(&mut self,position:Position) -> Card {
+	fn popCard(&mut self,position:Position) -> Result<Card,SolverError> {
 		let stack = self.resolvePositionMut(position);	
-		return stack.pop().unwrap()
+		return stack.pop().ok_or(SolverError::EmptySource(position));
 	}
 
 	fn pushCard(&mut self,card:Card,position:Position) {
@@ -465,15 +426,21 @@ impl Game {
 	}
 
 
+	// the key the current board is remembered under, which with --symmetry is the same for boards that differ only by suit
+	fn checksum(&self) -> String {
+		if self.options.symmetry { return symmetry::canonicalChecksum(&self.board) }
+		return checksumBoard(&self.board);
+	}
+
 	fn registerBoard(&mut self) -> bool {
-		let checksum = checksumBoard(&self.board);
+		let checksum = self.checksum();
 	
 		if self.boardSet.contains_key(&checksum) {
-			self.repeatsAvoided = self.repeatsAvoided + 1;
+			self.repeatsAvoided += 1;
 			return true;
 		}
 		self.boardSet.insert(checksum,true);
-		if self.boardSet.len() > ABANDON_THRESHOLD as usize { // give up after a certain point
+		if self.boardSet.len() > self.abandonThreshold as usize { // give up after a certain point
 			self.abandoned = true;
 			return true;
 		}
@@ -482,98 +449,92 @@ impl Game {
 		return false
 	}
 
-	fn recordMove(&mut self,source:Position,target:Position,extent:u16) {
-		let sourceStack = self.resolvePosition(source);
-		let targetStack = self.resolvePosition(target);
-	
-		let cardName = cardName(sourceStack.last(), " ".to_string());
-		// let msg = format!("Move: {0} From: {1:?}/{2:?} To: {3:?}/{4:?} ",cardName,sourceStack,source.stackType,targetStack,target.stackType);
-		let msg = String::from("n");
+	fn recordMove(&mut self,source:Position,target:Position,card:Card,extent:u16) {
 		// record the move
 		self.gameMoves.push(GameMove {
 			source,
 			target,
-			msg,
-			extent
+			card,
+			extent,
+			group: self.group
 		});
 	
 	}
 
+	// Play a legal move as a single supermove.  Every card moved along the way is recorded under a new group so the whole thing can be undone as one step.
+	// Playing a new move abandons anything that could have been redone
+	fn playMove(&mut self,legalMove:&LegalMove) -> Result<(),SolverError> {
+		self.group = self.gameMoves.last().map_or(0, |m| m.group + 1);
+		self.redoMoves.clear();
+
+		// for TABLEAU -> TABLEAU, use move extent
+		if legalMove.extent > 1 && matches!(legalMove.source.stackType,StackType::TABLEAU) && matches!(legalMove.target.stackType,StackType::TABLEAU ) {
+			return self.moveExtent(legalMove.source, legalMove.target, legalMove.extent);
+		}
+		return self.moveCard(legalMove.source, legalMove.target, legalMove.extent);
+	}
+
 	// We move an extent by moving extent-1 cards to free cells, moving the inner most card in the extent, then moving the remaining from the cells in reverse order
 	// e.g. if we have an extent of values 5,4,3 moving to a target stack where top card is 6, move 3, 4 to free cells, move 5 -> target stack, then 4,3 to target stack in that order
-	// this totals to (extent-1) * 2 + 1 total moves.  This amount should be used when undoing this action
-	// assume there are enough free cells to do this
-	fn moveExtent(&mut self,source:Position,target:Position,extent:u16) {
+	// this totals to (extent-1) * 2 + 1 total moves, all recorded under the same group so they are undone together
+	// there must be enough free cells to do this, otherwise nothing is moved and an error is returned
+	fn moveExtent(&mut self,source:Position,target:Position,extent:u16) -> Result<(),SolverError> {
 		// let sourceStack = self.resolvePosition(source);
 		
 		// println!("Move extent {0:?} to {1:?} extent {2:?}",source,target,extent);
 		let freeCells = findFreeCells(&self.board);
 		// the number of free cells must be at least the extent-1.  That is, we can move 1 card when theres no free cells, 2 if 1 free cell, etc.
-		if freeCells.len() >= (extent as usize - 1) {
-			for i in 0..extent as usize -1 {
-				let cellPosition = freeCells[i];
-				self.moveCard(source,cellPosition,extent);
-			}
-			self.moveCard(source,target,extent);
-			for i in (0..extent as usize -1).rev() {
-				let cellPosition = freeCells[i];
-				self.moveCard(cellPosition,target,extent);
-			}
+		if freeCells.len() < (extent as usize - 1) {
+			return Err(SolverError::NotEnoughCells { extent, freeCells: freeCells.len() as u16 });
+		}
 
+		let cellsUsed = &freeCells[..extent as usize - 1];
+		for cellPosition in cellsUsed.iter() {
+			self.moveCard(source,*cellPosition,extent)?;
+		}
+		self.moveCard(source,target,extent)?;
+		for cellPosition in cellsUsed.iter().rev() {
+			self.moveCard(*cellPosition,target,extent)?;
 		}
-	}
 
-	fn moveCard(&mut self,source:Position,target:Position,extent:u16) {
-		self.recordMove(source, target, extent);
-		
-		let sourceStack = self.resolvePosition(source);
-		// println!("Move card: {0:?} as part of extent {1:?}",sourceStack,extent);		// // make the move
-		if sourceStack.len() == 0 {
-			// self.print("About to panic!!!");
-			println!("{:?}",source);
-			thread::sleep(time::Duration::from_secs(10));
-			
-		}	
+		return Ok(());
+	}
 
-		let card = self.popCard(source);
+	fn moveCard(&mut self,source:Position,target:Position,extent:u16) -> Result<(),SolverError> {
+		let card = self.popCard(source)?;
 		self.pushCard(card, target);
+		self.recordMove(source, target, card, extent);
 	
 		self.totalMoves += 1;
 
-		if self.totalMoves % 1000 == 0 {
+		if self.render && self.totalMoves.is_multiple_of(1000) {
 			self.print("Playing") ;
 		}
 	
+		return Ok(());
 	}
 	
-	fn undoLastMove(&mut self) {
-		if self.gameMoves.len() > 0 {
-			let gameMove = self.gameMoves.pop().unwrap(); // pull off the last move
+	fn undoLastMove(&mut self) -> Result<(),SolverError> {
+		let gameMove = self.gameMoves.pop().ok_or(SolverError::NothingToUndo)?; // pull off the last move
 
-			
-
-			let card = self.popCard(gameMove.target);
-			self.pushCard(card,gameMove.source);
-			
-			// self.totalMoves -= 1;
-			
+		let card = self.popCard(gameMove.target)?;
+		self.pushCard(card,gameMove.source);
+		
+		// self.totalMoves -= 1;
 
-		} else {
-			println!("Ran out of moves to undo!");
-			process::exit(1);
-		}
+		return Ok(());
 	}
 
 
-	// determine if moving the card to the target stack constitues a legal move
-	fn isLegalMove(&self,card:&Card,target:Position,extentLength:u16) -> bool {
+	// determine if the rules allow moving the card on its own to the target stack, whether or not the solver would
+	fn isAllowedMove(&self,card:&Card,target:Position) -> bool {
 
 		let targetStack = self.resolvePosition(target);
 		if matches!(target.stackType, StackType::GOAL)  {
 			//  two conditions.  The card is an Ace, and the goal is empty
 			//  -or- the target's card is the same suit, and exactly one less in card value
-			if targetStack.len() == 0 {
-				return (card.value == 1)				
+			if targetStack.is_empty() {
+				return card.value == 1
 			}
 			// check if card value is same suit and exactly +1 in value
 			let targetCard = targetStack.last().unwrap();
@@ -581,18 +542,27 @@ impl Game {
 		}
 
 		if matches!(target.stackType, StackType::CELL ) {
-			return targetStack.len() == 0 // our only requiremnt if the target is a Cell is that the stack is empty
+			return targetStack.is_empty() // our only requiremnt if the target is a Cell is that the stack is empty
 		}
 
 		// target is a stack, no need to check
-		if targetStack.len() == 0 {
+		if targetStack.is_empty() {
 			return card.value == 13 // only a King can target an empty stack
 		}
 
 		// for all other TABLEAU moves, the top of the target stack must be same suit and one GREATER in value
 		let targetCard = targetStack.last().unwrap();
-		return targetCard.suit == card.suit && targetCard.value == (card.value+1) && !isBlockingMove(card, targetStack, extentLength);
-	
+		return targetCard.suit == card.suit && targetCard.value == (card.value+1);
+	}
+
+	// determine if moving the card to the target stack constitues a legal move: one the rules allow that doesn't block its suit
+	fn isLegalMove(&self,card:&Card,target:Position,extentLength:u16) -> bool {
+		if !self.isAllowedMove(card, target) { return false }
+
+		let targetStack = self.resolvePosition(target);
+		if !matches!(target.stackType, StackType::TABLEAU) || targetStack.is_empty() { return true }
+		let maxExtent = self.board.cells.len() as u16 + 1;
+		return !isBlockingMove(card, targetStack, extentLength, maxExtent);
 	}
 
 	// even though a card may have up to 3 legal moves, only one of them make sense to make in any given circumstance
@@ -600,43 +570,43 @@ impl Game {
 
 		
 		let sourceStack = self.resolvePosition(source);
-		if (sourceStack.len() > 0) { // cannot move anything from an empty stack
+		if !sourceStack.is_empty() { // cannot move anything from an empty stack
 			let mut card = sourceStack.last().unwrap();
 
 			// first check, for each goal stack, if move to goal is a legal move
-			for (stackIndex,goalStack) in self.board.goals.iter().enumerate() {
+			for stackIndex in 0..self.board.goals.len() {
 				let target = Position { stackIndex,stackType:StackType::GOAL};
 				if self.isLegalMove(card, target, 1) { return Some(LegalMove{source,target,extent:1}) } 
 			}
 
-			// short-circuit here if source stack is fully ordered.  
-			if matches!(source.stackType,StackType::TABLEAU) && isFullyOrdered(&self.board, sourceStack) { return None } // no reason to move fully ordered card except to goal ( see isFullyOrdered for full definition )
 
 			let mut extent = 0; 
 			
 			if matches!(source.stackType,StackType::TABLEAU) {
 				// stack to stack moves will use an extent
-				extent = findExtent(&self.board,&sourceStack);
+				extent = findExtent(&self.board,sourceStack);
 				if extent > 0 {
 					card = sourceStack.get(sourceStack.len() - extent as usize).unwrap()
 				} else {
-					return None; // if we found no extent from a source that is a Tableau, it means there's nothing that can be moved from that stack
+					// The run on top is longer than the free cells + 1 can carry, so apart from the goal ( tried above ) there's nowhere useful
+					// for its top card: it fits only on the card it is sitting on, and parking it in a cell just uses the cells up
+					return None;
 				}
 			}
 
 			// consider all moves that target the Tableau
-			for (i,targetStack) in self.board.stacks.iter().enumerate() {
-				let target = Position { stackIndex: i,stackType: StackType::TABLEAU};
-				if self.isLegalMove(&card, target, extent) { return Some(LegalMove{source,target,extent}) }
+			for stackIndex in 0..self.board.stacks.len() {
+				let target = Position { stackIndex,stackType: StackType::TABLEAU};
+				if self.isLegalMove(card, target, extent) { return Some(LegalMove{source,target,extent}) }
 			}
 
 			// only thing left is targeting free cells
 			if matches!(source.stackType,StackType::CELL) { return None } // a card in a cell should only move to a goal or stack, which have already been considered.  Short-circuit here if our card is in a cell
 			
-			let freeCells = findFreeCells(&self.board);
-			if freeCells.len() > 0 && extent <= 1 {
+			// an extent with nowhere to go can still be taken apart into the cells one card at a time, to get at what is under it
+			if let Some(cell) = findFreeCells(&self.board).first() {
 				return Some( LegalMove { source, 
-					target: freeCells.get(0).copied().unwrap(),
+					target: *cell,
 					extent:1}); // move to the first free cell available
 			}
 
@@ -645,147 +615,747 @@ impl Game {
 		return None;
 	}
 
+	// every move the search would try from the current position, in the ordering's order
+	fn orderedMoves(&mut self) -> Vec<LegalMove> {
+		let mut moves: Vec<LegalMove> = moveSources().filter_map(|source| self.findLegalMove(source)).collect();
+		self.ordering.order(&self.board, &mut moves);
+		return moves;
+	}
+
 	// Make the given move and recursively continue playing from the new configuration.
 	// That is, we will make that move, then follow that line of the possibility tree recursively.  Otherwise, we fail out of the function
-	fn moveAndPlayOn(&mut self,legalMove:LegalMove ) -> bool {
+	fn moveAndPlayOn(&mut self,legalMove:LegalMove ) -> Result<bool,SolverError> {
 
 
-		// for TABLEAU -> TABLEAU, use move extent
-		if legalMove.extent > 1 && matches!(legalMove.source.stackType,StackType::TABLEAU) && matches!(legalMove.target.stackType,StackType::TABLEAU ) {
-			self.moveExtent(legalMove.source, legalMove.target, legalMove.extent);
-		} else {
-			self.moveCard(legalMove.source, legalMove.target, legalMove.extent);
-		}
+		self.playMove(&legalMove)?;
+		self.bestGoal = self.bestGoal.max(countGoal(&self.board));
 
-		if isSuccess(&self.board) { return true } // check for success
+		if isSuccess(&self.board) { return Ok(true) } // check for success
 		
 		let repeatBoard = self.registerBoard();
-		
+		let deadEnd = !repeatBoard && self.isDeadEnd(); // check after registering, so reaching the same dead end again is just a repeat
+		if deadEnd { self.deadEnds += 1 }
 
-		if !repeatBoard {  // don't continue unless move wasn't a repeat ( classic example of too many negatives:  continue if not repeated)
-			let success = self.cycleThroughCards(); // recursively attempt to solve the new board configuration
-			if success { return true } // the path from this configuration succeeded, so return true
+		if !repeatBoard && !deadEnd {  // don't continue unless move wasn't a repeat ( classic example of too many negatives:  continue if not repeated)
+			let success = self.cycleThroughCards()?; // recursively attempt to solve the new board configuration
+			if success { return Ok(true) } // the path from this configuration succeeded, so return true
 		}
 
 		// at this point, we know that this configuration wasn't a success, it might be a repeat, or its attempt to solve from the new configuration resulted in failure
-		// in either case, we undo the move we just made
-		
-		if legalMove.extent > 1 {
-			let totalExtentMoves = (legalMove.extent-1)*2 + 1;  // each extent move is recorded as individual moves, so we need to back them all out individually
-			// println!("Undo extent move: {:?} ",legalMove);
-			for i in 0..totalExtentMoves  {
-				// println!("Undo extent {0} totalEtentMoves {1} index {2}",legalMove.extent,totalExtentMoves,i);
-				self.undoLastMove() 
-			}
-		} else { 
-			// println!("Undo standard move: {:?} ",legalMove);
-			self.undoLastMove() 
-		}
+		// in either case, we undo the move we just made.  An extent move is recorded as individual moves under one group, so this backs them all out
+		self.undoSupermove()?;
+		self.backtracks += 1;
 
 
-		return false // return the fact that this did not succeed
+		return Ok(false) // return the fact that this did not succeed
 
 	}
 
 	// our fundamental game loop.  Iterate over every Tableau and Cell stack, finding each legal move in the current configuration
 	// then make that move.  This function will be called recursively from the moveAndPlanOn() to attempt to win from the new configuration
-	fn cycleThroughCards(&mut self) -> bool {
+	fn cycleThroughCards(&mut self) -> Result<bool,SolverError> {
+		if interrupt::requested() { return Err(SolverError::Interrupted) }
+
 		self.stackSize += 1;
+		self.maxStackSize = self.maxStackSize.max(self.stackSize);
+		self.nodes += 1;
 
 		let mut success = false;
 
-		let mut allMoves : Vec<LegalMove> = Vec::new();
+		for lm in self.orderedMoves() {
+			
+			// thread::sleep(time::Duration::from_secs(1));
+			success = self.moveAndPlayOn(lm)?;
+			if success { break }
+		}
+
+		self.stackSize -= 1;
+		return Ok(success);
+	}
 
-		for stackIndex in 0..14 { // we will resolve this index as 10 Tableau stacks and 4 cells
-			let mut source: Position;
+	// Attempt to win from whatever position the board is in, which may be a fresh deal or a position from the middle of a game
+	// with cards already in the goals and cells.
+	fn solve(&mut self) -> Result<bool,SolverError> {
+		self.board.validate()?; // the solver relies on every card being present exactly once
 
-			// determine the source position
-			if stackIndex > 3 {
-				source = Position { stackIndex: stackIndex - 4,stackType:StackType::TABLEAU}
-			} else {
-				source = Position { stackIndex,stackType: StackType::CELL }
-			}
+		if isSuccess(&self.board) { return Ok(true) } // nothing left to do
 
-			match self.findLegalMove(source) {
-				Some(lm) => {
-					allMoves.push(lm);
-				},
-				None => {}
-			}			
+		self.ordering = self.options.ordering.build();
+		if let Some(playouts) = self.options.playouts { return self.solveByPlayouts(playouts) }
+		self.registerBoard(); // the starting position counts as seen, so no line of play can cycle back to it
+		return self.cycleThroughCards();
+	}
+
+	// Run the solver on a copy of the current board, exploring at most budget unique boards, and report the first move of any solution found.
+	// The game itself is left untouched
+	fn hint(&self,budget:u32) -> Result<Hint,SolverError> {
+		if isSuccess(&self.board) { return Ok(Hint::Won) }
+
+		let mut solver = Game::fromBoard(self.board.clone(), self.tally);
+		solver.abandonThreshold = budget;
+		solver.render = false;
+		solver.options = self.options;
+
+		if !solver.solve()? {
+			if solver.abandoned { return Ok(Hint::Unknown) }
+			return Ok(Hint::Lost);
 		}
 
-		// allMoves now has a list of legal moves
-		allMoves.sort_by_key(|lm| match lm.target.stackType {
-			StackType::GOAL => 0,
-			StackType::TABLEAU => 1,
-			_ => 2
-		});
+		// the first supermove ends with a card landing on its real target, even when the extent passed through the cells on the way
+		let first = &solver.gameMoves[0];
+		let target = solver.gameMoves.iter().take_while(|m| m.group == first.group).last().unwrap().target;
 
-		for lm in allMoves {
-			
-			// thread::sleep(time::Duration::from_secs(1));
-			success = self.moveAndPlayOn(lm);
-			if success { break }
+		return Ok(Hint::Move(LegalMove { source: first.source, target, extent: first.extent }));
+	}
+
+	fn describeHint(&self,hint:&Hint) -> String {
+		match hint {
+			Hint::Move(lm) => {
+				let sourceStack = self.resolvePosition(lm.source);
+				let cardCount = if lm.extent > 1 { lm.extent as usize } else { 1 };
+				let card = sourceStack.get(sourceStack.len() - cardCount);
+				let name = cardName(card, "?".to_string());
+				let name = name.trim();
+				if cardCount > 1 {
+					let cards = if cardCount == 2 { "card" } else { "cards" };
+					return format!("Hint: move {name} and the {0} {cards} above it from {1} to {2}",cardCount-1,positionName(lm.source),positionName(lm.target));
+				}
+				return format!("Hint: move {name} from {0} to {1}",positionName(lm.source),positionName(lm.target));
+			},
+			Hint::Won => "Hint: the game is already won".to_string(),
+			Hint::Unknown => "Hint: no solution found within limits".to_string(),
+			Hint::Lost => "Hint: provably lost, no line from here wins".to_string()
 		}
+	}
 
-		self.stackSize -= 1;
-		return success;
+	// print a line of text just below the board drawn by print()
+	fn printMessage(&self,msg: &str) {
+		self.printMessageLine(0, msg);
+		println!();
 	}
 
-	fn replayGame(&mut self) {
-		// rewind the entire game based on the move stack
-		let moveCopy = self.gameMoves.to_vec();
-		
-		// # undo all moves
-		for i in 0..moveCopy.len() {
-			self.undoLastMove();
-			self.print("Rewinding        ");
-			thread::sleep(time::Duration::from_millis(10));
+	// print a line of text the given number of lines below the board, clearing whatever was left on that line
+	fn printMessageLine(&self,line: u16,msg: &str) {
+		let term = terminal::stdout();
+		let row = self.messageRow(terminalSize());
+
+		let _ = term.act(Action::SetForegroundColor(Color::Reset));
+		let _ = term.act(Action::MoveCursorTo(1,row+line));
+		print!("{msg}");
+		let _ = term.act(Action::ClearTerminal(Clear::UntilNewLine));
+	}
+
+
+	
+
+}
+
+
+// read a board written in the text notation ( see notation.rs ) from a file.  If there's no such file, a deal code can be given instead
+fn loadBoard(path:&str) -> Board {
+	let text = match std::fs::read_to_string(path) {
+		Ok(t) => t,
+		Err(e) => {
+			if let Ok(board) = Board::fromDealCode(path) { return board }
+			eprintln!("Unable to read {path}: {e}");
+			process::exit(1);
 		}
-		
-		for m in moveCopy {
-			self.moveCard(m.source,m.target,m.extent);
-			if m.extent <= 1 {
-				self.print("Replay") ;
-				thread::sleep(time::Duration::from_millis(100));
+	};
+	match Board::parse(&text) {
+		Ok(board) => board,
+		Err(e) => {
+			eprintln!("Unable to load {path}: {e}");
+			process::exit(1);
+		}
+	}
+}
+
+// Ctrl-C stopped a single solve: draw the board and dashboard as they stand, put the terminal back and exit the way a process
+// killed by Ctrl-C would.  Modes that don't draw while solving start from a cleared screen here so the board isn't drawn over their output
+fn exitInterrupted(game:&Game,message:&str) -> ! {
+	if !game.render {
+		let _ = terminal::stdout().act(Action::ClearTerminal(Clear::All));
+	}
+	game.print("Interrupted");
+	game.printMessage(message);
+	interrupt::restoreTerminal();
+	process::exit(130);
+}
+
+// ask the solver for a hint from the given position, or a fresh deal if none is given, rather than playing through a batch
+fn hintMode(board:Option<Board>,budget:u32,appearance:Appearance,options:SolverOptions) {
+	let tally = Tally::default();
+	let mut game = match board {
+		Some(b) => Game::fromBoard(b, tally),
+		None => Game::new(tally)
+	};
+	game.appearance = appearance;
+	game.options = options;
+
+	let _ = terminal::stdout().act(Action::ClearTerminal(Clear::All));
+	game.print("Hint");
+	match game.hint(budget) {
+		Ok(hint) => game.printMessage(&game.describeHint(&hint)),
+		Err(SolverError::Interrupted) => exitInterrupted(&game, "Interrupted before a hint was found"),
+		Err(e) => game.printMessage(&format!("Hint failed: {e}"))
+	}
+}
+
+// solve a single position loaded from a file, optionally saving the solution so it can be replayed later
+fn solveMode(board:Board,savePath:Option<&String>,appearance:Appearance,options:SolverOptions) {
+	let tally = Tally::default();
+	let mut game = Game::fromBoard(board, tally);
+	game.appearance = appearance;
+	game.options = options;
+
+	let _ = terminal::stdout().act(Action::ClearTerminal(Clear::All));
+	let success = match game.solve() {
+		Ok(s) => s,
+		Err(SolverError::Interrupted) => exitInterrupted(&game, &format!("Interrupted after {0} moves",game.totalMoves)),
+		Err(e) => {
+			eprintln!("Cannot solve: {e}");
+			process::exit(1);
+		}
+	};
+	game.print("Finished");
+
+	// saved before rating, so Ctrl-C while rating doesn't lose the solution
+	if let (true,Some(path)) = (success,savePath) {
+		if let Err(e) = std::fs::write(path, game.solutionText()) {
+			eprintln!("Unable to save solution to {path}: {e}");
+		}
+	}
+
+	let outcome = if success { "Winnable" } else if game.abandoned { "Abandoned" } else { "Loser" };
+	game.printMessage(&format!("{outcome} after {0} moves",game.totalMoves));
+	if success {
+		match game.difficulty() {
+			Ok(d) => game.printMessageLine(1,&format!("Difficulty {0:.1} ({1}): {2} nodes, {3} backtracks, {4} moves at shortest, {5} forced into cells",d.score,d.band(),d.nodes,d.backtracks,d.shortestLength,d.forcedCells)),
+			Err(SolverError::Interrupted) => exitInterrupted(&game, &format!("Winnable after {0} moves, interrupted while rating the difficulty",game.totalMoves)),
+			Err(e) => game.printMessageLine(1,&format!("Unable to rate the difficulty: {e}"))
+		}
+		println!();
+	}
+}
+
+// read a saved solution ( see solution.rs ) from a file, returning the game with all of its moves played
+fn loadSolutionFile(path:&str) -> Game {
+	let tally = Tally::default();
+	let text = match std::fs::read_to_string(path) {
+		Ok(t) => t,
+		Err(e) => {
+			eprintln!("Unable to read {path}: {e}");
+			process::exit(1);
+		}
+	};
+	match Game::loadSolution(&text, tally) {
+		Ok(game) => game,
+		Err(e) => {
+			eprintln!("Unable to load {path}: {e}");
+			process::exit(1);
+		}
+	}
+}
+
+// step through a saved solution interactively
+fn replayMode(path:&str,expanded:bool,appearance:Appearance) {
+	let mut game = loadSolutionFile(path);
+	game.appearance = appearance;
+
+	let result = replay::ReplayViewer::new(game, expanded).and_then(|mut viewer| viewer.run());
+	if let Err(e) = result {
+		eprintln!("Unable to replay {path}: {e}");
+		process::exit(1);
+	}
+}
+
+// write the replay of a saved solution out as an asciinema cast file
+fn castMode(path:&str,outPath:&str,expanded:bool,delayMs:u64,appearance:Appearance) {
+	let mut game = loadSolutionFile(path);
+	game.appearance = appearance;
+
+	let result = cast::exportCast(&mut game, path, expanded, delayMs)
+		.map_err(|e| e.to_string())
+		.and_then(|text| std::fs::write(outPath, text).map_err(|e| e.to_string()));
+	if let Err(e) = result {
+		eprintln!("Unable to export {path}: {e}");
+		process::exit(1);
+	}
+}
+
+// solve a position and print the solution as a numbered list of moves, without drawing the board
+fn movesMode(board:Board,options:SolverOptions) {
+	let tally = Tally::default();
+	let mut game = Game::fromBoard(board, tally);
+	game.render = false;
+	game.options = options;
+
+	match game.solve() {
+		Ok(true) => {
+			for (i,line) in game.describeHistory().iter().enumerate() {
+				println!("{0:>4}. {line}",i+1);
+			}
+		},
+		Ok(false) => println!("{0}",if game.abandoned { "Abandoned" } else { "Loser" }),
+		Err(SolverError::Interrupted) => exitInterrupted(&game, &format!("Interrupted after {0} moves",game.totalMoves)),
+		Err(e) => eprintln!("Cannot solve: {e}")
+	}
+}
+
+// count the distinct winning lines from a position and print how open or forced it is
+fn linesMode(board:Board,limit:usize,budget:usize,options:SolverOptions) {
+	let tally = Tally::default();
+	let mut game = Game::fromBoard(board, tally);
+	game.render = false;
+	game.options = options;
+
+	let count = match game.countLines(limit, budget) {
+		Ok(c) => c,
+		Err(SolverError::Interrupted) => exitInterrupted(&game, "Interrupted while counting lines"),
+		Err(e) => {
+			eprintln!("Cannot count lines: {e}");
+			process::exit(1);
+		}
+	};
+	let atLeast = if count.atLeast || !count.complete { "at least " } else { "" };
+	println!("{atLeast}{0} winning solver lines, {1} positions explored",count.lines,count.positions);
+	if !count.complete {
+		println!("Budget of {budget} positions reached, so the count is only what was found");
+	} else if let Some((lineLength,forced)) = count.forced {
+		if count.lines > 0 { println!("{forced} of the {lineLength} moves on the first line are forced, judged over every legal move") }
+	} else if count.lines > 0 && !count.atLeast {
+		println!("Budget of {budget} positions reached before the forced moves on the first line were found");
+	}
+}
+
+// Annotate a solution with the moves a player could have gone wrong on.  The file is either a saved solution or a position to solve first
+fn criticalMode(path:&str,budget:u32,outPath:Option<&str>,options:SolverOptions) {
+	let isSolution = std::fs::read_to_string(path).is_ok_and(|text| text.lines().any(|l| l.trim().starts_with("move:")));
+	let mut game = if isSolution {
+		loadSolutionFile(path)
+	} else {
+		let tally = Tally::default();
+		let mut game = Game::fromBoard(loadBoard(path), tally);
+		game.render = false;
+		game.options = options;
+		match game.solve() {
+			Ok(true) => game,
+			Ok(false) => {
+				println!("{0}, so there is no solution to annotate",if game.abandoned { "Abandoned" } else { "Loser" });
+				return;
+			},
+			Err(SolverError::Interrupted) => exitInterrupted(&game, &format!("Interrupted after {0} moves",game.totalMoves)),
+			Err(e) => {
+				eprintln!("Cannot solve: {e}");
+				process::exit(1);
 			}
 		}
+	};
+	game.options = options;
+
+	let steps = match game.criticalMoves(budget) {
+		Ok(s) => s,
+		Err(SolverError::Interrupted) => exitInterrupted(&game, "Interrupted while judging the moves of the solution"),
+		Err(e) => {
+			eprintln!("Cannot analyse {path}: {e}");
+			process::exit(1);
+		}
+	};
 
+	let mut annotated = game.startingBoard().toText();
+	let mut cardMoves = game.solutionMoves().into_iter();
+	for (i,step) in steps.iter().enumerate() {
+		let heading = format!("{0:>4}. {1}   {2}",i+1,history::describeSupermove(&step.moves),step.summary());
+		println!("{heading}");
+		annotated += &format!("# {0}\n",heading.trim_start());
+		for (description,verdict) in step.alternatives.iter().filter(|(_,v)| *v != critical::Verdict::Wins) {
+			println!("        {0:<8} {description}",verdict.name());
+			annotated += &format!("#     {0:<8} {description}\n",verdict.name());
+		}
+		for m in cardMoves.by_ref().take(step.moves.len()) {
+			annotated += &format!("move: {m}\n");
+		}
 	}
+	let critical = steps.iter().filter(|s| s.isCritical()).count();
+	let only = steps.iter().filter(|s| s.isOnlyMove()).count();
+	println!("{critical} of {0} moves are critical, {only} of them the only move",steps.len());
 
-	
+	if let Some(out) = outPath {
+		if let Err(e) = std::fs::write(out, annotated) {
+			eprintln!("Unable to write {out}: {e}");
+		}
+	}
+}
 
+// the value following a --name option on the command line
+fn optionValue<'a>(args:&'a [String],name:&str) -> Option<&'a str> {
+	return args.iter().position(|a| a == name).and_then(|i| args.get(i+1)).map(|v| v.as_str());
 }
 
+// the options that stand on their own, where every other option takes the value after it
+const SWITCHES: [&str;3] = ["--expanded","--symmetry","--uniform-playouts"];
 
-fn main() {
-	let term = terminal::stdout();
-	term.act(Action::ClearTerminal(Clear::All));
+// the first argument after the mode that is neither an option nor an option's value, e.g. the file in "hint --style unicode d2.txt"
+fn firstArgument(args:&[String]) -> Option<&str> {
+	let mut rest = args.iter().skip(2);
+	while let Some(arg) = rest.next() {
+		if !arg.starts_with("--") { return Some(arg) }
+		if !SWITCHES.contains(&arg.as_str()) { rest.next(); }
+	}
+	return None;
+}
+
+// the rest of a batch report line after the deal code
+fn reportFields(outcome:Outcome,nodes:u64,backtracks:u64,difficulty:Option<Difficulty>) -> String {
+	let rating = match difficulty {
+		Some(d) => format!("{0},{1},{2:.1},{3}",d.shortestLength,d.forcedCells,d.score,d.band()),
+		None => ",,,".to_string()
+	};
+	return format!("{0},{nodes},{backtracks},{rating}",outcome.name());
+}
 
-	let mut tally = Tally {
-		totalGames: 0,
-		winnable: 0,
-		losers: 0,
-		abandoned: 0
+// Drop the report lines for deals from next on, which a resumed batch is about to play again.  This includes the line for the deal
+// that was interrupted, so the finished report is the same as if the batch had never stopped
+fn trimReport(path:&str,next:u32) -> std::io::Result<()> {
+	let text = match std::fs::read_to_string(path) {
+		Ok(text) => text,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+		Err(e) => return Err(e)
 	};
+	let kept: String = text.lines()
+		.filter(|line| line.split(',').next().and_then(|index| index.parse::<u32>().ok()).is_none_or(|index| index < next))
+		.map(|line| format!("{line}\n"))
+		.collect();
+	return std::fs::write(path, kept);
+}
+
+// Play a batch of deals, drawing each as it is solved.  Deal i is dealt from seed masterSeed+i, so a batch can be repeated exactly.
+// With a report path, a CSV line is written for each deal with its deal code, outcome and difficulty.
+// With a results store, deals it has already settled with the same solver options are counted from it instead of being solved again,
+// and new results are added to it unless they come from --playouts.
+// With a checkpoint path, progress is saved before each deal, and a batch with a checkpoint already saved carries on from it ( with its
+// own seed and number of games ), appending to the report.  The checkpoint is removed once the batch is finished.
+// Ctrl-C stops the batch after marking the deal being solved as interrupted in the report, leaving the checkpoint to resume from.
+// A failed write to the report stops the batch too, rather than leaving a report with lines missing
+fn batchMode(games:u32,masterSeed:u64,reportPath:Option<&str>,resultsPath:Option<&str>,checkpointPath:Option<&str>,appearance:Appearance,options:SolverOptions) -> std::io::Result<()> {
+	let mut checkpoint = Checkpoint {
+		masterSeed,
+		games,
+		next: 0,
+		tally: Tally::default(),
+		options: options.tag()
+	};
+	if let Some(path) = checkpointPath {
+		match Checkpoint::load(path) {
+			Ok(Some(saved)) if saved.options != checkpoint.options => {
+				eprintln!("Checkpoint {path} was saved by a batch with the options '{0}', run with them again to resume it",saved.options);
+				process::exit(1);
+			},
+			Ok(Some(saved)) => checkpoint = saved,
+			Ok(None) => {},
+			Err(e) => {
+				eprintln!("Unable to read checkpoint {path}: {e}");
+				process::exit(1);
+			}
+		}
+	}
+	let resuming = checkpoint.next > 0;
+	if let Some(path) = reportPath.filter(|_| resuming) {
+		if let Err(e) = trimReport(path, checkpoint.next) {
+			eprintln!("Unable to resume report {path}: {e}");
+		}
+	}
+
+	let term = terminal::stdout();
+	let _ = term.act(Action::ClearTerminal(Clear::All));
+
+	let mut store = resultsPath.and_then(|path| match ResultsStore::open(path) {
+		Ok(store) => Some(store),
+		Err(e) => {
+			eprintln!("Unable to open results {path}: {e}");
+			None
+		}
+	});
+
+	let mut report = reportPath.and_then(|path| match std::fs::OpenOptions::new().write(true).create(true).append(resuming).truncate(!resuming).open(path) {
+		Ok(file) => Some(file),
+		Err(e) => {
+			eprintln!("Unable to create {path}: {e}");
+			None
+		}
+	});
+	if let Some(file) = report.as_mut().filter(|_| !resuming) {
+		writeln!(file,"game,seed,deal,outcome,nodes,backtracks,shortest_length,forced_cells,difficulty,band")?;
+	}
+
+	let mut tally = checkpoint.tally;
+	let optionsTag = options.tag();
 	
-	for _ in 0..1000 {
-		
-		let mut game = Game::new(tally);
+	for index in checkpoint.next..checkpoint.games {
+		if let Some(path) = checkpointPath {
+			checkpoint.next = index;
+			checkpoint.tally = tally;
+			if let Err(e) = checkpoint.save(path) {
+				eprintln!("Unable to save checkpoint {path}: {e}");
+			}
+		}
+
+		let seed = checkpoint.masterSeed.wrapping_add(index as u64);
+		let board = dealBoard(seed);
+		let dealCode = board.dealCode().unwrap_or_default();
+
+		let known = store.as_ref().and_then(|s| s.get(&fingerprint(&board))).filter(|r| r.isSettled(ABANDON_THRESHOLD, &optionsTag));
+		if let Some(record) = known {
+			record.outcome.addTo(&mut tally);
+			if let Some(file) = report.as_mut() {
+				writeln!(file,"{index},{seed},{dealCode},{0}",reportFields(record.outcome,record.nodes,record.backtracks,record.difficulty))?;
+			}
+			continue;
+		}
+
+		let mut game = Game::fromBoard(board.clone(), tally);
+		game.appearance = appearance;
+		game.options = options;
+
+		// a winnable deal is rated as part of solving it, so Ctrl-C while rating interrupts the deal the same way
+		let solved = game.solve().and_then(|success| {
+			let difficulty = if success { Some(game.difficulty()?) } else { None };
+			return Ok((success,difficulty));
+		});
+		let (success,difficulty) = match solved {
+			Ok(s) => s,
+			Err(SolverError::Interrupted) => {
+				if let Some(file) = report.as_mut() {
+					writeln!(file,"{index},{seed},{dealCode},interrupted,{0},{1},,,,",game.nodes,game.backtracks)?;
+				}
+				game.print("Interrupted");
+				game.printMessageLine(0, &format!("Interrupted game {index} ( seed {seed} ) after {0} nodes",game.nodes));
+				game.printMessageLine(1, &format!("Played {0}: {1} winnable, {2} losers, {3} abandoned",tally.totalGames,tally.winnable,tally.losers,tally.abandoned));
+				if let Some(path) = checkpointPath {
+					game.printMessageLine(2, &format!("Progress saved to {path}, run again with --checkpoint {path} to resume"));
+				}
+				interrupt::restoreTerminal();
+				println!();
+				return Ok(());
+			},
+			Err(e) => { // report the problem and move on to the next deal rather than losing the whole batch
+				game.printMessage(&format!("Solver error: {e}"));
+				continue;
+			}
+		};
 
-		let success = game.cycleThroughCards();
+		let outcome = Outcome::of(&game, success);
+		outcome.addTo(&mut tally);
+		game.print("Finished");
 
-		tally.totalGames += 1;
-		if (success) { tally.winnable += 1 }
-		else { tally.losers += 1 }
+		if let Some(file) = report.as_mut() {
+			writeln!(file,"{index},{seed},{dealCode},{0}",reportFields(outcome,game.nodes,game.backtracks,difficulty))?;
+		}
+		if let (Some(store),None) = (store.as_mut(),options.playouts) { // playouts' nodes and abandons aren't the search's, so aren't stored
+			if let Err(e) = store.record(&board, &game, success, difficulty) {
+				game.printMessage(&format!("Unable to save result: {e}"));
+			}
+		}
+	}
 
-		if (game.abandoned) { tally.abandoned += 1 }
-		game.print(("Finished"));
+	// a checkpoint left behind would make the next batch with it think this one still had deals to play
+	if let Some(path) = checkpointPath {
+		match std::fs::remove_file(path) {
+			Err(e) if e.kind() != std::io::ErrorKind::NotFound => eprintln!("Unable to remove checkpoint {path}, delete it before running with it again: {e}"),
+			_ => {}
+		}
+	}
+	return Ok(());
+}
 
-		// if success {
-			// game.replayGame();
-		// }
+fn main() {
+	let args: Vec<String> = std::env::args().collect();
+	let appearance = Appearance::fromArgs(&args);
+	let options = SolverOptions::fromArgs(&args);
+	interrupt::install();
+
+	match args.get(1).map(|a| a.as_str()) {
+		Some("hint") => { // hint [file] [--budget n]
+			let budget = optionValue(&args,"--budget").and_then(|b| b.parse::<u32>().ok()).unwrap_or(HINT_BUDGET);
+			let board = firstArgument(&args).map(loadBoard);
+			hintMode(board,budget,appearance,options);
+			return;
+		},
+		Some("solve") => { // solve <file> [solution]
+			let path = args.get(2).expect("usage: seahaven solve <file> [solution]");
+			solveMode(loadBoard(path),args.get(3).filter(|a| !a.starts_with("--")),appearance,options);
+			return;
+		},
+		Some("replay") => { // replay <solution> [--expanded]
+			let path = args.get(2).expect("usage: seahaven replay <solution> [--expanded]");
+			replayMode(path,args.iter().any(|a| a == "--expanded"),appearance);
+			return;
+		},
+		Some("cast") => { // cast <solution> <out.cast> [--expanded] [--delay ms]
+			let usage = "usage: seahaven cast <solution> <out.cast> [--expanded] [--delay ms]";
+			let path = args.get(2).expect(usage);
+			let outPath = args.get(3).expect(usage);
+			let delayMs = optionValue(&args,"--delay").and_then(|d| d.parse::<u64>().ok()).unwrap_or(250);
+			castMode(path,outPath,args.iter().any(|a| a == "--expanded"),delayMs,appearance);
+			return;
+		},
+		Some("svg") => { // svg <file> <out.svg>
+			let usage = "usage: seahaven svg <file> <out.svg>";
+			let board = loadBoard(args.get(2).expect(usage));
+			let outPath = args.get(3).expect(usage);
+			if let Err(e) = std::fs::write(outPath, svg::boardSvg(&board,&appearance)) {
+				eprintln!("Unable to write {outPath}: {e}");
+			}
+			return;
+		},
+		Some("html") => { // html <solution> <out.html>
+			let usage = "usage: seahaven html <solution> <out.html>";
+			let path = args.get(2).expect(usage);
+			let outPath = args.get(3).expect(usage);
+			let mut game = loadSolutionFile(path);
+			game.appearance = appearance;
+			let result = svg::solutionHtml(&mut game, path)
+				.map_err(|e| e.to_string())
+				.and_then(|text| std::fs::write(outPath, text).map_err(|e| e.to_string()));
+			if let Err(e) = result {
+				eprintln!("Unable to export {path}: {e}");
+			}
+			return;
+		},
+		Some("generate") => { // generate --band easy|medium|hard|unsolvable [--min score] [--max score] [--count n] [--seed s] [--max-seeds m] [--out file]
+			let target = match generate::Target::fromArgs(&args) {
+				Some(t) => t,
+				None => {
+					eprintln!("usage: seahaven generate --band easy|medium|hard|unsolvable [--min score] [--max score] [--count n] [--seed s] [--max-seeds m] [--out file]");
+					process::exit(1);
+				}
+			};
+			let count = optionValue(&args,"--count").and_then(|c| c.parse::<u32>().ok()).unwrap_or(10);
+			let startSeed = optionValue(&args,"--seed").and_then(|s| s.parse::<u64>().ok()).unwrap_or_else(|| thread_rng().gen());
+			let maxSeeds = optionValue(&args,"--max-seeds").and_then(|m| m.parse::<u64>().ok()).unwrap_or(10000);
+
+			let mut out: Box<dyn Write> = match optionValue(&args,"--out") {
+				Some(path) => match std::fs::File::create(path) {
+					Ok(file) => Box::new(file),
+					Err(e) => {
+						eprintln!("Unable to create {path}: {e}");
+						process::exit(1);
+					}
+				},
+				None => Box::new(std::io::stdout())
+			};
+			match generate::generateDeals(target, count, startSeed, maxSeeds, options, &mut out) {
+				Ok(found) if found < count => eprintln!("Only found {found} of {count} deals in {maxSeeds} seeds"),
+				Ok(_) => {},
+				Err(e) => eprintln!("Unable to write deals: {e}")
+			}
+			return;
+		},
+		Some("results") => { // results <file> [--solution <deal or fingerprint> <out>]
+			let path = args.get(2).expect("usage: seahaven results <file> [--solution <deal or fingerprint> <out>]");
+			let store = ResultsStore::open(path).unwrap_or_else(|e| {
+				eprintln!("Unable to open results {path}: {e}");
+				process::exit(1);
+			});
+			match optionValue(&args,"--solution") {
+				Some(key) => {
+					let out = args.iter().position(|a| a == "--solution").and_then(|i| args.get(i+2))
+						.expect("usage: seahaven results <file> --solution <deal or fingerprint> <out>");
+					match store.find(key).map(|r| r.solutionText()) {
+						Some(Ok(Some(text))) => if let Err(e) = std::fs::write(out, text) { eprintln!("Unable to write {out}: {e}") },
+						Some(Ok(None)) => eprintln!("No solution recorded for {key}"),
+						Some(Err(e)) => eprintln!("Bad result for {key}: {e}"),
+						None => eprintln!("{key} is not in {path}")
+					}
+				},
+				None => for line in store.summaryLines() { println!("{line}") }
+			}
+			return;
+		},
+		Some("verify-keys") => { // verify-keys [--games n] [--seed s] [--steps n]
+			let games = optionValue(&args,"--games").and_then(|g| g.parse::<u32>().ok()).unwrap_or(1000);
+			let seed = optionValue(&args,"--seed").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1);
+			let steps = optionValue(&args,"--steps").and_then(|s| s.parse::<u32>().ok()).unwrap_or(200);
+
+			let (checked,failures) = canonical::verifyKeys(seed, games, steps);
+			for failure in failures.iter() {
+				println!("{failure}");
+			}
+			println!("Checked {checked} positions on random walks: {0} failures",failures.len());
+			if !failures.is_empty() { process::exit(1) }
+			return;
+		},
+		Some("verify-pruning") => { // verify-pruning [--positions n] [--cards n] [--seed s]
+			let positions = optionValue(&args,"--positions").and_then(|p| p.parse::<u32>().ok()).unwrap_or(1000);
+			let cards = optionValue(&args,"--cards").and_then(|c| c.parse::<usize>().ok()).unwrap_or(14);
+			let seed = optionValue(&args,"--seed").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1);
+
+			let (checked,winnable,failures) = pruning::verifyPruning(positions, cards, seed, options);
+			for failure in failures.iter() {
+				println!("{failure}");
+			}
+			println!("Checked {checked} positions of {cards} cards ( {winnable} winnable ): {0} disagreements",failures.len());
+			if !failures.is_empty() { process::exit(1) }
+			return;
+		},
+		Some("tournament") => { // tournament [--entrants file] [--games n] [--seed s] [--out file]
+			let entrants = match optionValue(&args,"--entrants") {
+				Some(path) => tournament::Entrant::load(path).unwrap_or_else(|e| {
+					eprintln!("Unable to read entrants {path}: {e}");
+					process::exit(1);
+				}),
+				None => tournament::Entrant::orderings(options)
+			};
+			let games = optionValue(&args,"--games").and_then(|g| g.parse::<u32>().ok()).unwrap_or(20);
+			let seed = optionValue(&args,"--seed").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1);
+
+			let mut out: Box<dyn Write> = match optionValue(&args,"--out") {
+				Some(path) => match std::fs::File::create(path) {
+					Ok(file) => Box::new(file),
+					Err(e) => {
+						eprintln!("Unable to create {path}: {e}");
+						process::exit(1);
+					}
+				},
+				None => Box::new(std::io::stdout())
+			};
+			if let Err(e) = tournament::runTournament(&entrants, games, seed, &mut out) {
+				eprintln!("Unable to write results: {e}");
+			}
+			return;
+		},
+		Some("lines") => { // lines <file> [--limit n] [--budget n]
+			let path = args.get(2).expect("usage: seahaven lines <file> [--limit n] [--budget n]");
+			let limit = optionValue(&args,"--limit").and_then(|l| l.parse::<usize>().ok()).unwrap_or(lines::DEFAULT_LIMIT);
+			let budget = optionValue(&args,"--budget").and_then(|b| b.parse::<usize>().ok()).unwrap_or(ABANDON_THRESHOLD as usize);
+			linesMode(loadBoard(path),limit,budget,options);
+			return;
+		},
+		Some("critical") => { // critical <solution or position> [--budget n] [--out file]
+			let path = args.get(2).expect("usage: seahaven critical <solution or position> [--budget n] [--out file]");
+			let budget = optionValue(&args,"--budget").and_then(|b| b.parse::<u32>().ok()).unwrap_or(HINT_BUDGET);
+			criticalMode(path,budget,optionValue(&args,"--out"),options);
+			return;
+		},
+		Some("moves") => { // moves <file>
+			let path = args.get(2).expect("usage: seahaven moves <file>");
+			movesMode(loadBoard(path),options);
+			return;
+		},
+		Some("deal") => { // deal [seed]: print a deal in text notation, as a starting point for a position file
+			let board = args.get(2).and_then(|s| s.parse::<u64>().ok()).map_or_else(initializeBoard, dealBoard);
+			println!("# deal {0}",board.dealCode().unwrap_or_default());
+			print!("{}",board.toText());
+			return;
+		},
+		_ => {}
+	}
 
+	let games = optionValue(&args,"--games").and_then(|g| g.parse::<u32>().ok()).unwrap_or(1000);
+	let masterSeed = optionValue(&args,"--seed").and_then(|g| g.parse::<u64>().ok()).unwrap_or_else(|| thread_rng().gen());
+	if let Err(e) = batchMode(games,masterSeed,optionValue(&args,"--report"),optionValue(&args,"--results"),optionValue(&args,"--checkpoint"),appearance,options) {
+		interrupt::restoreTerminal();
+		eprintln!("Unable to write report: {e}");
+		process::exit(1);
 	}
-    
 }
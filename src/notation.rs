@@ -0,0 +1,149 @@
+// Plain text notation for a Board, so positions from real play sessions can be written down and loaded back in.
+//
+//   goals: 3H - 5C -
+//   cells: KS - - 4D
+//   stack: 10H 9H 2C
+//   stack: -
+//   ... ( one line per stack, 10 in total )
+//
+// Cards are written the same way cardName prints them ( value then suit ), and "-" marks an empty goal, cell or stack.
+// A goal is written as its top card only, since a goal always holds every card of that suit from the Ace up.
+// Stacks are listed bottom card first.  Blank lines and anything after a '#' are ignored.
+
+use crate::{cardName, emptyBoard, Board, Card};
+use crate::validate::BoardError;
+
+// A card is its value then its suit, e.g. 10H.  The suit may also be the symbol the Unicode card style draws ( 10♥ ), so cards can be
+// copied back from the board as it was shown
+pub(crate) fn parseCard(text:&str) -> Result<Card,String> {
+	let (valueStr,suitChar) = match text.char_indices().last() {
+		Some((i,c)) if i > 0 => (&text[..i],c),
+		_ => return Err(format!("bad card '{text}'"))
+	};
+
+	let suit = match suitChar {
+		'H' | '♥' => 0,
+		'D' | '♦' => 1,
+		'C' | '♣' => 2,
+		'S' | '♠' => 3,
+		_ => return Err(format!("bad suit in card '{text}'"))
+	};
+
+	let value = match valueStr {
+		"A" => 1,
+		"J" => 11,
+		"Q" => 12,
+		"K" => 13,
+		_ => match valueStr.parse::<u8>() {
+			Ok(v) if (2..=10).contains(&v) => v,
+			_ => return Err(format!("bad value in card '{text}'"))
+		}
+	};
+
+	return Ok(Card { suit, value });
+}
+
+// parse a whitespace separated list of cards, where "-" is an empty slot
+fn parseSlots(text:&str) -> Result<Vec<Option<Card>>,String> {
+	return text.split_whitespace()
+		.map(|t| if t == "-" { Ok(None) } else { parseCard(t).map(Some) })
+		.collect();
+}
+
+fn slotName(card: Option<&Card>) -> String {
+	return cardName(card, "-".to_string()).trim().to_string();
+}
+
+impl Board {
+
+	// parse a board and make sure it is a legal position
+	pub(crate) fn parse(text:&str) -> Result<Board,BoardError> {
+		let board = Board::parseUnchecked(text).map_err(BoardError::Malformed)?;
+		board.validate()?;
+		return Ok(board);
+	}
+
+	fn parseUnchecked(text:&str) -> Result<Board,String> {
+		let mut board = emptyBoard();
+		let mut stackCount = 0;
+
+		for (lineNo,rawLine) in text.lines().enumerate() {
+			let line = rawLine.split('#').next().unwrap().trim();
+			if line.is_empty() { continue }
+
+			let (label,rest) = match line.split_once(':') {
+				Some(parts) => parts,
+				None => return Err(format!("line {0}: expected 'goals:', 'cells:' or 'stack:'",lineNo+1))
+			};
+			let slots = parseSlots(rest).map_err(|e| format!("line {0}: {e}",lineNo+1))?;
+
+			match label.trim() {
+				"goals" => {
+					if slots.len() > board.goals.len() { return Err(format!("line {0}: too many goals",lineNo+1)) }
+					for (i,slot) in slots.iter().enumerate() {
+						if let Some(top) = slot {
+							for value in 1..=top.value {
+								board.goals[i].push(Card { suit: top.suit, value });
+							}
+						}
+					}
+				},
+				"cells" => {
+					if slots.len() > board.cells.len() { return Err(format!("line {0}: too many cells",lineNo+1)) }
+					for (i,slot) in slots.iter().enumerate() {
+						if let Some(card) = slot { board.cells[i].push(*card) }
+					}
+				},
+				"stack" => {
+					if stackCount >= board.stacks.len() { return Err(format!("line {0}: too many stacks",lineNo+1)) }
+					board.stacks[stackCount].extend(slots.iter().flatten());
+					stackCount += 1;
+				},
+				other => return Err(format!("line {0}: unknown section '{other}'",lineNo+1))
+			}
+		}
+
+		return Ok(board);
+	}
+
+	pub(crate) fn toText(&self) -> String {
+		let goals: Vec<String> = self.goals.iter().map(|s| slotName(s.last())).collect();
+		let cells: Vec<String> = self.cells.iter().map(|s| slotName(s.last())).collect();
+
+		let mut text = format!("goals: {0}\ncells: {1}\n",goals.join(" "),cells.join(" "));
+		for stack in self.stacks.iter() {
+			let cards: Vec<String> = stack.iter().map(|c| slotName(Some(c))).collect();
+			let cards = if cards.is_empty() { "-".to_string() } else { cards.join(" ") };
+			text += &format!("stack: {cards}\n");
+		}
+
+		return text;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parsesLettersAndSymbols() {
+		assert_eq!(parseCard("10H"), Ok(Card { suit: 0, value: 10 }));
+		assert_eq!(parseCard("AS"), Ok(Card { suit: 3, value: 1 }));
+		assert_eq!(parseCard("Q♦"), Ok(Card { suit: 1, value: 12 }));
+		assert_eq!(parseCard("10♣"), Ok(Card { suit: 2, value: 10 }));
+	}
+
+	#[test]
+	fn rejectsBadCardsWithoutPanicking() {
+		for text in ["", "H", "♥", "A", "A♥♥", "1H", "11S", "A☃", "éH", "KX"] {
+			assert!(parseCard(text).is_err(), "{0} should not parse", text);
+		}
+	}
+
+	#[test]
+	fn boardWithSymbolsRoundTrips() {
+		let text = crate::dealBoard(3).toText().replace('H', "♥").replace('S', "♠");
+		let board = Board::parse(&text).unwrap();
+		assert_eq!(board.toText(), crate::dealBoard(3).toText());
+	}
+}
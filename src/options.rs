@@ -0,0 +1,64 @@
+// Switches for how the solver searches, as opposed to how it draws ( see appearance.rs ).  The defaults are how the solver
+// normally runs, and the flags change that so runs can be compared against each other.
+//
+//   --symmetry                      treat boards that differ only by which suit is which as the same position
+//   --no-dead-ends <names | all>    turn off some or all of the dead end detectors ( buried, cells, circular, see deadend.rs )
+//   --ordering <policy>             the order moves are tried in, see ordering.rs
+//   --playouts <n>                  play n random games instead of searching, see montecarlo.rs
+
+use crate::optionValue;
+use crate::deadend::DeadEnds;
+use crate::montecarlo::Playouts;
+use crate::ordering::OrderingPolicy;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SolverOptions {
+	pub(crate) symmetry: bool, // collapse boards that are the same up to relabelling the suits, see symmetry.rs
+	pub(crate) deadEnds: DeadEnds, // which dead end detectors prune the search
+	pub(crate) ordering: OrderingPolicy,
+	pub(crate) playouts: Option<Playouts> // random playouts instead of the depth first search
+}
+
+impl Default for SolverOptions {
+	fn default() -> Self {
+		return SolverOptions { symmetry: false, deadEnds: DeadEnds::all(), ordering: OrderingPolicy::GoalFirst, playouts: None };
+	}
+}
+
+impl SolverOptions {
+
+	pub(crate) fn fromArgs(args:&[String]) -> SolverOptions {
+		return SolverOptions {
+			symmetry: args.iter().any(|a| a == "--symmetry"),
+			deadEnds: optionValue(args,"--no-dead-ends").map_or(DeadEnds::all(), DeadEnds::without),
+			ordering: OrderingPolicy::fromArgs(args),
+			playouts: optionValue(args,"--playouts").and_then(|p| p.parse::<u32>().ok()).map(|count| Playouts {
+				count,
+				seed: optionValue(args,"--playout-seed").and_then(|s| s.parse::<u64>().ok()).unwrap_or(1),
+				uniform: args.iter().any(|a| a == "--uniform-playouts")
+			})
+		};
+	}
+
+	// The options as the flags that choose them, e.g. "--ordering random --ordering-seed 3 --symmetry", so what a result was
+	// solved with can be recorded alongside it.  The ordering is always given, so two sets of options have the same tag only if
+	// they search the same way
+	pub(crate) fn tag(&self) -> String {
+		let mut flags = vec![format!("--ordering {0}",self.ordering.name())];
+		if let OrderingPolicy::Random(seed) = self.ordering {
+			flags.push(format!("--ordering-seed {seed}"));
+		}
+		let off = self.deadEnds.off();
+		if !off.is_empty() {
+			flags.push(format!("--no-dead-ends {0}",off.join(",")));
+		}
+		if self.symmetry {
+			flags.push("--symmetry".to_string());
+		}
+		if let Some(playouts) = self.playouts {
+			flags.push(format!("--playouts {0} --playout-seed {1}",playouts.count,playouts.seed));
+			if playouts.uniform { flags.push("--uniform-playouts".to_string()) }
+		}
+		return flags.join(" ");
+	}
+}
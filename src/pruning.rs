@@ -0,0 +1,179 @@
+// Checking the solver's pruning against an exhaustive search.  The solver only tries one move per source, only moves whole runs
+// onto another stack, refuses blocking moves ( isBlockingMove ), leaves runs too long to move alone ( findExtent ) and backs out of dead ends
+// ( deadend.rs ).  Each of those is meant to throw away only lines that can't win.  `seahaven verify-pruning` tests that: it builds
+// small random endgame positions, solves each with the solver as it normally runs and again with a plain search that tries every
+// legal move, and reports any position where the two disagree about whether it can be won.
+//
+//   seahaven verify-pruning [--positions n] [--cards n] [--seed s]      ( plus any solver options, e.g. --no-dead-ends all )
+//
+// The first run of this turned up two holes, both now fixed.  A run with nowhere to go was never taken apart into the cells, so the
+// card under it could be stuck for good, and isBlockingMove refused to build a run of 5 over a lower card of its suit though 4 free
+// cells can still move it.  Fixing them turned 2 of the 60 deals from seed 1 from losers into wins.
+
+use std::collections::HashSet;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::{emptyBoard, interrupt, moveSources, stackOrderedCount, Board, Card, Game, LegalMove, Position, Stack, StackType, Tally};
+use crate::error::SolverError;
+use crate::canonical::stateKey;
+use crate::options::SolverOptions;
+
+// An endgame with the given number of cards still to play: each suit's goal built up some way, and the remaining cards spread over a
+// few of the cells and the first few stacks so they bury each other
+fn randomPosition(cards:usize,rng:&mut StdRng) -> Board {
+	let mut board = emptyBoard();
+
+	let mut heights = [13u8;4];
+	for _ in 0..cards.min(52) {
+		let suits: Vec<usize> = (0..4).filter(|s| heights[*s] > 0).collect();
+		heights[*suits.choose(rng).unwrap()] -= 1;
+	}
+
+	let mut remaining: Vec<Card> = Vec::new();
+	for (suit,height) in heights.iter().enumerate() {
+		board.goals[suit].extend((1..=*height).map(|value| Card { suit: suit as u8, value }));
+		remaining.extend((height+1..=13).map(|value| Card { suit: suit as u8, value }));
+	}
+	remaining.shuffle(rng);
+
+	let columns = rng.gen_range(2..=5);
+	for card in remaining {
+		let freeCell = board.cells.iter().position(|c| c.is_empty());
+		match freeCell {
+			Some(cell) if rng.gen_range(0..6) == 0 => board.cells[cell].push(card),
+			_ => board.stacks[rng.gen_range(0..columns)].push(card)
+		}
+	}
+	return board;
+}
+
+// where a card can go on the goals, if anywhere
+fn goalFor(board:&Board,card:&Card) -> Option<usize> {
+	return board.goals.iter().position(|g| match g.last() {
+		Some(top) => top.suit == card.suit && top.value + 1 == card.value,
+		None => card.value == 1
+	});
+}
+
+fn canStack(target:&Stack,card:&Card) -> bool {
+	match target.last() {
+		Some(top) => top.suit == card.suit && top.value == card.value + 1,
+		None => card.value == 13
+	}
+}
+
+// Every legal move on board, whether or not the solver would consider it: any card to the goal or a stack it fits on, any run the
+// free cells allow onto a stack it fits on, and any tableau card into the first free cell
+pub(crate) fn everyLegalMove(board:&Board) -> Vec<LegalMove> {
+	let mut moves = Vec::new();
+	let freeCells = board.cells.iter().filter(|c| c.is_empty()).count();
+	let freeCell = board.cells.iter().position(|c| c.is_empty());
+
+	for source in moveSources() {
+		let stack = stackAt(board, source);
+		let card = match stack.last() { Some(c) => *c, None => continue };
+		let isTableau = matches!(source.stackType,StackType::TABLEAU);
+
+		if let Some(goal) = goalFor(board, &card) {
+			moves.push(LegalMove { source, target: Position { stackIndex: goal, stackType: StackType::GOAL }, extent: 1 });
+		}
+		if let (true,Some(cell)) = (isTableau,freeCell) {
+			moves.push(LegalMove { source, target: Position { stackIndex: cell, stackType: StackType::CELL }, extent: 1 });
+		}
+
+		let longest = if isTableau { (stackOrderedCount(stack) as usize).min(freeCells + 1) } else { 1 };
+		for extent in 1..=longest {
+			let base = stack[stack.len() - extent];
+			for (target,targetStack) in board.stacks.iter().enumerate() {
+				if isTableau && target == source.stackIndex { continue }
+				if canStack(targetStack, &base) {
+					moves.push(LegalMove { source, target: Position { stackIndex: target, stackType: StackType::TABLEAU }, extent: extent as u16 });
+				}
+			}
+		}
+	}
+	return moves;
+}
+
+fn stackAt(board:&Board,position:Position) -> &Stack {
+	return match position.stackType {
+		StackType::GOAL => &board.goals[position.stackIndex],
+		StackType::CELL => &board.cells[position.stackIndex],
+		StackType::TABLEAU => &board.stacks[position.stackIndex]
+	};
+}
+
+// every position one move on from board
+fn successors(board:&Board) -> Vec<Board> {
+	return everyLegalMove(board).iter().map(|legalMove| {
+		let mut next = board.clone();
+		let source = match legalMove.source.stackType {
+			StackType::CELL => &mut next.cells[legalMove.source.stackIndex],
+			_ => &mut next.stacks[legalMove.source.stackIndex]
+		};
+		let moved = source.split_off(source.len() - legalMove.extent as usize);
+		match legalMove.target.stackType {
+			StackType::GOAL => next.goals[legalMove.target.stackIndex].extend(moved),
+			StackType::CELL => next.cells[legalMove.target.stackIndex].extend(moved),
+			StackType::TABLEAU => next.stacks[legalMove.target.stackIndex].extend(moved)
+		}
+		return next;
+	}).collect();
+}
+
+// depth first search over every legal move, never visiting a position twice
+fn exhaustiveWin(board:&Board,seen:&mut HashSet<String>) -> bool {
+	if board.goals.iter().map(|g| g.len()).sum::<usize>() == 52 {
+		return true;
+	}
+	if !seen.insert(stateKey(board)) {
+		return false;
+	}
+	return successors(board).iter().any(|next| exhaustiveWin(next, seen));
+}
+
+// Returns how many positions were checked, how many of those could be won, and a description of each disagreement
+pub(crate) fn verifyPruning(positions:u32,cards:usize,seed:u64,options:SolverOptions) -> (u32,u32,Vec<String>) {
+	let tally = Tally::default();
+	let mut rng = StdRng::seed_from_u64(seed);
+	let mut winnable = 0;
+	let mut failures: Vec<String> = Vec::new();
+	let mut checked = 0;
+
+	for i in 0..positions {
+		if interrupt::requested() {
+			eprintln!("Interrupted after {checked} positions");
+			break;
+		}
+		let board = randomPosition(cards, &mut rng);
+		let exhaustive = exhaustiveWin(&board, &mut HashSet::new());
+
+		let mut game = Game::fromBoard(board.clone(), tally);
+		game.render = false;
+		game.options = options;
+		let pruned = match game.solve() {
+			Ok(s) => s,
+			Err(SolverError::Interrupted) => {
+				eprintln!("Interrupted after {checked} positions");
+				break;
+			},
+			Err(e) => {
+				checked += 1;
+				failures.push(format!("position {i}: solver error: {e}\n{0}",board.toText()));
+				continue;
+			}
+		};
+
+		checked += 1;
+		if exhaustive { winnable += 1 }
+		if pruned != exhaustive && !game.abandoned {
+			let verdict = if exhaustive { "pruned a winning line" } else { "won a position the exhaustive search lost" };
+			failures.push(format!("position {i}: solver {verdict}\n{0}",board.toText()));
+		}
+	}
+
+	return (checked,winnable,failures);
+}
@@ -0,0 +1,170 @@
+// Interactive replay of a saved solution.  The game is rewound to its starting position and played forward from the redo history,
+// either a supermove at a time or, when expanded, one card move at a time.
+//
+//   space        play / pause
+//   right / n    step forward
+//   left / p     step back
+//   home / end   jump to the start / end
+//   0-9 enter    jump to move N
+//   + / -        faster / slower
+//   e            show supermoves expanded or as single steps
+//   q / esc      quit
+
+use std::io::Write;
+use std::time::Duration;
+
+use terminal::{Action, Clear, Event, KeyCode, Retrieved, Value};
+
+use crate::{interrupt, Game};
+use crate::error::SolverError;
+use crate::history::describeSupermove;
+
+const SPEEDS_MS: [u64;6] = [1000,500,250,100,50,10]; // delay between steps while playing
+
+pub(crate) struct ReplayViewer {
+	game: Game,
+	playing: bool,
+	expanded: bool, // step through individual card moves rather than whole supermoves
+	speed: usize, // index into SPEEDS_MS
+	jumpTo: String // digits typed so far for a jump to move N
+}
+
+impl ReplayViewer {
+
+	// start a viewer for a game whose moves have all been played.  The game is rewound to the beginning
+	pub(crate) fn new(mut game: Game,expanded: bool) -> Result<ReplayViewer,SolverError> {
+		game.rewind()?;
+		game.render = false;
+
+		return Ok(ReplayViewer { game, playing: false, expanded, speed: 3, jumpTo: String::new() });
+	}
+
+	// how far into the replay we are, and how many steps there are in total, counted in the current step size
+	fn progress(&self) -> (usize,usize) {
+		if self.expanded {
+			let played = self.game.gameMoves.len();
+			return (played, played + self.game.redoMoves.len());
+		}
+		let played = self.game.gameMoves.last().map_or(0, |m| m.group as usize + 1);
+		let total = self.game.redoMoves.first().or(self.game.gameMoves.last()).map_or(0, |m| m.group as usize + 1);
+		return (played,total);
+	}
+
+	fn stepForward(&mut self) -> Result<bool,SolverError> {
+		if self.expanded { return self.game.redoCard() }
+		return self.game.redo();
+	}
+
+	fn stepBack(&mut self) -> Result<bool,SolverError> {
+		if self.game.gameMoves.is_empty() { return Ok(false) }
+		if self.expanded { self.game.undoCard()? } else { self.game.undo()? }
+		return Ok(true);
+	}
+
+	fn jump(&mut self,step: usize) -> Result<(),SolverError> {
+		while self.progress().0 > step {
+			self.stepBack()?;
+		}
+		while self.progress().0 < step && self.stepForward()? {}
+		return Ok(());
+	}
+
+	fn lastMoveText(&self) -> String {
+		if self.expanded {
+			return self.game.gameMoves.last().map_or(String::new(), |m| m.describe());
+		}
+		return self.game.supermoves().last().map_or(String::new(), |moves| describeSupermove(moves));
+	}
+
+	fn draw(&self) {
+		let (played,total) = self.progress();
+		let state = if self.playing { "playing" } else { "paused " };
+		let steps = if self.expanded { "card moves" } else { "supermoves" };
+		self.game.print(&format!("Replay  {played}/{total} {steps}  {state}  {0}ms per step   ",SPEEDS_MS[self.speed]));
+
+		self.game.printMessageLine(0, &self.lastMoveText());
+		let jump = if self.jumpTo.is_empty() { String::new() } else { format!("   jump to {0}_",self.jumpTo) };
+		self.game.printMessageLine(2, &format!("space play/pause  left/right step  home/end  digits+enter jump  +/- speed  e expand  q quit{jump}"));
+		let _ = std::io::stdout().flush(); // best effort, like the drawing itself
+	}
+
+	// handle a key press, returning false when the viewer should close
+	fn handleKey(&mut self,code: KeyCode) -> Result<bool,SolverError> {
+		match code {
+			KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+			KeyCode::Char(' ') => self.playing = !self.playing,
+			KeyCode::Right | KeyCode::Char('n') => { self.playing = false; self.stepForward()?; },
+			KeyCode::Left | KeyCode::Char('p') => { self.playing = false; self.stepBack()?; },
+			KeyCode::Home => self.jump(0)?,
+			KeyCode::End => self.jump(usize::MAX)?,
+			KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed + 1).min(SPEEDS_MS.len() - 1),
+			KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+			KeyCode::Char('e') => {
+				// switching to supermove steps part way through a supermove finishes it first, so we always land on a whole step
+				self.expanded = !self.expanded;
+				if !self.expanded {
+					let group = self.game.gameMoves.last().map(|m| m.group);
+					while group.is_some() && self.game.redoMoves.last().map(|m| m.group) == group {
+						self.game.redoCard()?;
+					}
+				}
+			},
+			KeyCode::Char(c) if c.is_ascii_digit() => self.jumpTo.push(c),
+			KeyCode::Backspace => { self.jumpTo.pop(); },
+			KeyCode::Enter => {
+				if let Ok(step) = self.jumpTo.parse::<usize>() {
+					self.playing = false;
+					self.jump(step)?;
+				}
+				self.jumpTo.clear();
+			},
+			_ => {}
+		}
+		return Ok(true);
+	}
+
+	pub(crate) fn run(&mut self) -> Result<(),SolverError> {
+		let term = terminal::stdout();
+		if let Err(e) = term.act(Action::EnableRawMode) {
+			eprintln!("Unable to read single key presses, keys will need Enter: {e}");
+		}
+		let _ = term.act(Action::HideCursor);
+		let _ = term.act(Action::ClearTerminal(Clear::All));
+
+		let result = self.eventLoop();
+
+		interrupt::restoreTerminal();
+		if let Err(e) = term.act(Action::DisableRawMode) {
+			eprintln!("Unable to restore the terminal: {e}");
+		}
+		println!();
+		return result;
+	}
+
+	fn eventLoop(&mut self) -> Result<(),SolverError> {
+		let term = terminal::stdout();
+		let mut height = 0;
+		let mut running = true;
+
+		while running {
+			// the board is drawn to the height of its longest stack, so start from a clean screen when that changes or the terminal is resized
+			let newHeight = self.game.board.stacks.iter().map(|s| s.len()).max().unwrap();
+			if newHeight != height {
+				let _ = term.act(Action::ClearTerminal(Clear::All));
+				height = newHeight;
+			}
+			self.draw();
+			let _ = term.act(Action::EnableRawMode); // failing was reported when the viewer started.  Every terminal handle turns raw mode off again when dropped, and drawing creates a few
+
+			let timeout = if self.playing { Some(Duration::from_millis(SPEEDS_MS[self.speed])) } else { None };
+			match term.get(Value::Event(timeout)) {
+				Ok(Retrieved::Event(Some(Event::Key(key)))) => running = self.handleKey(key.code)?,
+				Ok(Retrieved::Event(None)) => self.playing = self.stepForward()?, // timed out while playing, so advance, stopping at the end
+				Ok(Retrieved::Event(Some(Event::Resize))) => height = 0, // the layout will change, so redraw from a clean screen
+				_ => {}
+			}
+		}
+
+		return Ok(());
+	}
+}
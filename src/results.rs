@@ -0,0 +1,297 @@
+// A results store that outlives a single run: an append-only file with one line per solved deal, keyed by a fingerprint of the
+// starting board, so batches can skip deals that have already been settled and statistics can build up across runs.
+//
+// Each line holds tab separated fields
+//
+//   fingerprint deal outcome nodes backtracks threshold version options shortest_length forced_cells difficulty moves
+//
+// threshold is the abandon threshold the deal was solved with, version the solver version that solved it, and options the solver
+// options it ran with ( as SolverOptions::tag gives them ).  The last four fields are only filled in for winnable deals, with moves
+// the solution's moves ( as written after "move:" in a solution file ) separated by ';'.  Lines from before options were recorded
+// have one field fewer, and are read as solved with unknown options.
+// A deal can appear more than once, e.g. abandoned and later solved with a larger threshold.  The last line for a deal wins.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+
+use sha2::{Digest, Sha256};
+
+use crate::{Board, Game, Tally};
+use crate::dashboard::wilsonInterval;
+use crate::difficulty::Difficulty;
+use crate::error::SolverError;
+
+const SOLVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Outcome {
+	Winnable,
+	Loser,
+	Abandoned
+}
+
+impl Outcome {
+
+	pub(crate) fn of(game:&Game,success:bool) -> Outcome {
+		if success { return Outcome::Winnable }
+		if game.abandoned { return Outcome::Abandoned }
+		return Outcome::Loser;
+	}
+
+	pub(crate) fn name(&self) -> &'static str {
+		match self {
+			Outcome::Winnable => "winnable",
+			Outcome::Loser => "loser",
+			Outcome::Abandoned => "abandoned"
+		}
+	}
+
+	fn parse(text:&str) -> Option<Outcome> {
+		match text {
+			"winnable" => Some(Outcome::Winnable),
+			"loser" => Some(Outcome::Loser),
+			"abandoned" => Some(Outcome::Abandoned),
+			_ => None
+		}
+	}
+
+	// count a game with this outcome the same way a batch does
+	pub(crate) fn addTo(&self,tally:&mut Tally) {
+		tally.totalGames += 1;
+		match self {
+			Outcome::Winnable => tally.winnable += 1,
+			Outcome::Loser => tally.losers += 1,
+			Outcome::Abandoned => { tally.losers += 1; tally.abandoned += 1 }
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Record {
+	pub(crate) fingerprint: String,
+	pub(crate) deal: String,
+	pub(crate) outcome: Outcome,
+	pub(crate) nodes: u64,
+	pub(crate) backtracks: u64,
+	pub(crate) threshold: u32,
+	pub(crate) version: String,
+	pub(crate) options: String, // the solver options' tag, empty if not known
+	pub(crate) difficulty: Option<Difficulty>,
+	pub(crate) moves: Vec<String>
+}
+
+impl Record {
+
+	fn toLine(&self) -> String {
+		let rating = match self.difficulty {
+			Some(d) => format!("{0}\t{1}\t{2:.2}",d.shortestLength,d.forcedCells,d.score),
+			None => "\t\t".to_string()
+		};
+		return format!("{0}\t{1}\t{2}\t{3}\t{4}\t{5}\t{6}\t{7}\t{rating}\t{8}",
+			self.fingerprint,self.deal,self.outcome.name(),self.nodes,self.backtracks,self.threshold,self.version,self.options,self.moves.join(";"));
+	}
+
+	fn parse(line:&str) -> Option<Record> {
+		let mut fields: Vec<&str> = line.split('\t').collect();
+		if fields.len() == 11 {
+			fields.insert(7, "");
+		}
+		if fields.len() != 12 {
+			return None;
+		}
+		let outcome = Outcome::parse(fields[2])?;
+		let nodes = fields[3].parse::<u64>().ok()?;
+		let backtracks = fields[4].parse::<u64>().ok()?;
+
+		let difficulty = match outcome {
+			Outcome::Winnable => Some(Difficulty {
+				nodes,
+				backtracks,
+				shortestLength: fields[8].parse().ok()?,
+				forcedCells: fields[9].parse().ok()?,
+				score: fields[10].parse().ok()?
+			}),
+			_ => None
+		};
+
+		return Some(Record {
+			fingerprint: fields[0].to_string(),
+			deal: fields[1].to_string(),
+			outcome,
+			nodes,
+			backtracks,
+			threshold: fields[5].parse().ok()?,
+			version: fields[6].to_string(),
+			options: fields[7].to_string(),
+			difficulty,
+			moves: fields[11].split(';').filter(|m| !m.is_empty()).map(|m| m.to_string()).collect()
+		});
+	}
+
+	// true if solving the deal again with this abandon threshold and these solver options ( as a tag ) can't tell us anything new.
+	// Other options search differently, so give other nodes and solutions and may settle a deal these abandon, or the other way round
+	pub(crate) fn isSettled(&self,threshold:u32,options:&str) -> bool {
+		return self.options == options && (self.outcome != Outcome::Abandoned || self.threshold >= threshold);
+	}
+
+	// the solution file for a winnable deal, which can be replayed, cast etc like any other
+	pub(crate) fn solutionText(&self) -> Result<Option<String>,SolverError> {
+		if self.outcome != Outcome::Winnable {
+			return Ok(None);
+		}
+		let mut text = Board::fromDealCode(&self.deal)?.toText();
+		for m in self.moves.iter() {
+			text += &format!("move: {m}\n");
+		}
+		return Ok(Some(text));
+	}
+}
+
+// identifies a starting position, whether or not it is a fresh deal
+pub(crate) fn fingerprint(board:&Board) -> String {
+	let digest = Sha256::digest(board.toText().as_bytes());
+	return format!("{digest:x}");
+}
+
+pub(crate) struct ResultsStore {
+	records: HashMap<String,Record>,
+	order: Vec<String>, // fingerprints in the order they were first seen
+	file: File
+}
+
+impl ResultsStore {
+
+	// open the store, creating it if it doesn't exist yet.  Lines that can't be read are skipped with a warning rather than losing the rest
+	pub(crate) fn open(path:&str) -> std::io::Result<ResultsStore> {
+		let file = OpenOptions::new().create(true).append(true).read(true).open(path)?;
+		let mut records: HashMap<String,Record> = HashMap::new();
+		let mut order: Vec<String> = Vec::new();
+
+		for (lineNo,line) in BufReader::new(&file).lines().enumerate() {
+			let line = line?;
+			if line.trim().is_empty() || line.starts_with('#') {
+				continue;
+			}
+			match Record::parse(&line) {
+				Some(record) => {
+					if !records.contains_key(&record.fingerprint) {
+						order.push(record.fingerprint.clone());
+					}
+					records.insert(record.fingerprint.clone(), record);
+				},
+				None => eprintln!("{path} line {0}: unreadable result, skipped",lineNo+1)
+			}
+		}
+
+		return Ok(ResultsStore { records, order, file });
+	}
+
+	pub(crate) fn get(&self,fingerprint:&str) -> Option<&Record> {
+		return self.records.get(fingerprint);
+	}
+
+	// find a deal by its fingerprint or deal code
+	pub(crate) fn find(&self,key:&str) -> Option<&Record> {
+		return self.get(key).or_else(|| self.records.values().find(|r| r.deal == key));
+	}
+
+	// Record a game that has just been solved from the given starting board, with its difficulty if it was won
+	pub(crate) fn record(&mut self,board:&Board,game:&Game,success:bool,difficulty:Option<Difficulty>) -> std::io::Result<()> {
+		let outcome = Outcome::of(game, success);
+		let record = Record {
+			fingerprint: fingerprint(board),
+			deal: board.dealCode().unwrap_or_default(),
+			outcome,
+			nodes: game.nodes,
+			backtracks: game.backtracks,
+			threshold: game.abandonThreshold,
+			version: SOLVER_VERSION.to_string(),
+			options: game.options.tag(),
+			difficulty,
+			moves: if success { game.solutionMoves() } else { Vec::new() }
+		};
+
+		writeln!(self.file,"{0}",record.toLine())?;
+		if !self.records.contains_key(&record.fingerprint) {
+			self.order.push(record.fingerprint.clone());
+		}
+		self.records.insert(record.fingerprint.clone(), record);
+		return Ok(());
+	}
+
+	// statistics over every deal in the store
+	pub(crate) fn summaryLines(&self) -> Vec<String> {
+		let mut tally = Tally::default();
+		let mut bands: HashMap<&str,u32> = HashMap::new();
+		let mut versions: HashMap<&str,u32> = HashMap::new();
+		let mut totalNodes: u64 = 0;
+		let mut totalScore = 0.0;
+
+		for fingerprint in self.order.iter() {
+			let record = &self.records[fingerprint];
+			record.outcome.addTo(&mut tally);
+			totalNodes += record.nodes;
+			*versions.entry(&record.version).or_insert(0) += 1;
+			if let Some(d) = record.difficulty {
+				*bands.entry(d.band()).or_insert(0) += 1;
+				totalScore += d.score;
+			}
+		}
+
+		let mut lines = vec![
+			format!("Deals {0}",tally.totalGames),
+			format!("Winnable {0}  Losers: {1}  Abandoned {2}",tally.winnable,tally.losers - tally.abandoned,tally.abandoned)
+		];
+		if tally.totalGames > 0 {
+			let (low,high) = wilsonInterval(tally.winnable, tally.totalGames);
+			let decided = tally.totalGames - tally.abandoned;
+			lines.push(format!("Win Rate {0:.1}%  (95% CI {1:.1}% - {2:.1}%)",tally.winnable as f64 / tally.totalGames as f64 * 100.0,low*100.0,high*100.0));
+			if decided > 0 {
+				lines.push(format!("Win Rate of decided deals {0:.1}%",tally.winnable as f64 / decided as f64 * 100.0));
+			}
+			lines.push(format!("Mean Nodes {0:.0}",totalNodes as f64 / tally.totalGames as f64));
+		}
+		if tally.winnable > 0 {
+			let count = |band| bands.get(band).copied().unwrap_or(0);
+			lines.push(format!("Easy {0}  Medium {1}  Hard {2}  Mean Difficulty {3:.1}",count("easy"),count("medium"),count("hard"),totalScore / tally.winnable as f64));
+		}
+
+		let mut versions: Vec<(&str,u32)> = versions.into_iter().collect();
+		versions.sort();
+		for (version,count) in versions {
+			lines.push(format!("Solver {version}: {count} deals"));
+		}
+		return lines;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::options::SolverOptions;
+	use crate::ordering::OrderingPolicy;
+
+	const LOSER: &str = "ab12\tdeal\tloser\t100\t90\t500000\t0.1.0\t--ordering goal-first\t\t\t\t";
+
+	#[test]
+	fn settledOnlyWithTheSameOptions() {
+		let record = Record::parse(LOSER).unwrap();
+		assert_eq!(record.toLine(), LOSER);
+
+		let options = SolverOptions::default();
+		assert!(record.isSettled(500000, &options.tag()));
+		let random = SolverOptions { ordering: OrderingPolicy::Random(3), ..options };
+		assert!(!record.isSettled(500000, &random.tag()));
+		let symmetry = SolverOptions { symmetry: true, ..options };
+		assert!(!record.isSettled(500000, &symmetry.tag()));
+	}
+
+	#[test]
+	fn linesWithoutOptionsAreNeverSettled() {
+		let old = LOSER.replace("\t--ordering goal-first", "");
+		let record = Record::parse(&old).unwrap();
+		assert_eq!(record.options, "");
+		assert!(!record.isSettled(500000, &SolverOptions::default().tag()));
+	}
+}
@@ -0,0 +1,189 @@
+// Saving and loading a played game as a solution file: the starting board in the usual text notation ( see notation.rs ),
+// followed by one line per card move
+//
+//   move: <group> <source> <target> <extent> <card>
+//
+// e.g. "move: 8 S3 C1 2 6S".  Positions are written as G, C or S ( goal, cell, stack ) followed by the 1 based index, as shown by positionName.
+// The card is redundant, but makes the file readable and lets us check the moves still line up with the board when loading.
+// Moves sharing a group make up one supermove.  A group of one card has any extent, but a larger one is an extent move of
+// (extent-1)*2+1 card moves, all with that extent, and every card move in it must be one the rules allow on its own.
+
+use crate::{cardName, Board, Game, GameMove, Position, StackType, Tally};
+use crate::error::SolverError;
+use crate::notation::parseCard;
+use crate::validate::BoardError;
+
+fn positionCode(position:Position) -> String {
+	let kind = match position.stackType {
+		StackType::GOAL => "G",
+		StackType::CELL => "C",
+		StackType::TABLEAU => "S"
+	};
+	return format!("{kind}{0}",position.stackIndex+1);
+}
+
+fn parsePosition(text:&str) -> Result<Position,String> {
+	let mut chars = text.chars();
+	let (stackType,count) = match chars.next() {
+		Some('G') => (StackType::GOAL,4),
+		Some('C') => (StackType::CELL,4),
+		Some('S') => (StackType::TABLEAU,10),
+		_ => return Err(format!("bad position '{text}'"))
+	};
+	let index = chars.as_str();
+	match index.parse::<usize>() {
+		Ok(i) if i >= 1 && i <= count => Ok(Position { stackIndex: i-1, stackType }),
+		_ => Err(format!("bad position '{text}'"))
+	}
+}
+
+fn parseMove(text:&str) -> Result<GameMove,String> {
+	let fields: Vec<&str> = text.split_whitespace().collect();
+	if fields.len() != 5 {
+		return Err(format!("expected 'move: <group> <source> <target> <extent> <card>', found '{text}'"));
+	}
+	let group = fields[0].parse::<u32>().map_err(|_| format!("bad group '{0}'",fields[0]))?;
+	let extent = fields[3].parse::<u16>().map_err(|_| format!("bad extent '{0}'",fields[3]))?;
+
+	return Ok(GameMove {
+		source: parsePosition(fields[1])?,
+		target: parsePosition(fields[2])?,
+		card: parseCard(fields[4])?,
+		extent,
+		group
+	});
+}
+
+impl Game {
+
+	// the board as it was before any of the recorded moves were made
+	pub(crate) fn startingBoard(&self) -> Board {
+		let mut rewind = Game::fromBoard(self.board.clone(), self.tally);
+		for gameMove in self.gameMoves.iter().rev() {
+			let card = rewind.resolvePositionMut(gameMove.target).pop().unwrap();
+			rewind.pushCard(card, gameMove.source);
+		}
+		return rewind.board;
+	}
+
+	// each recorded move as it appears after "move:" in a solution file
+	pub(crate) fn solutionMoves(&self) -> Vec<String> {
+		return self.gameMoves.iter().map(|m| {
+			let name = cardName(Some(&m.card), "".to_string());
+			format!("{0} {1} {2} {3} {4}",m.group,positionCode(m.source),positionCode(m.target),m.extent,name.trim())
+		}).collect();
+	}
+
+	pub(crate) fn solutionText(&self) -> String {
+		let mut text = self.startingBoard().toText();
+		for m in self.solutionMoves() {
+			text += &format!("move: {m}\n");
+		}
+		return text;
+	}
+
+	// Load a solution file, returning the game with every move played.  Each move is checked against the card actually on top of its
+	// source and the rules for where it goes, and each group against the shape of a supermove, so a game that loads can be replayed,
+	// described and undone like one the solver played
+	pub(crate) fn loadSolution(text:&str,tally:Tally) -> Result<Game,SolverError> {
+		let mut boardText = String::new();
+		let mut moves: Vec<(usize,GameMove)> = Vec::new(); // with the line each came from
+
+		for (lineNo,line) in text.lines().enumerate() {
+			match line.trim().strip_prefix("move:") {
+				Some(rest) => {
+					let gameMove = parseMove(rest).map_err(|e| BoardError::Malformed(format!("line {0}: {e}",lineNo+1)))?;
+					moves.push((lineNo+1,gameMove));
+				},
+				None => {
+					boardText += line;
+					boardText += "\n";
+				}
+			}
+		}
+
+		let mut game = Game::fromBoard(Board::parse(&boardText)?, tally);
+		game.render = false;
+
+		for group in moves.chunk_by(|a,b| a.1.group == b.1.group) {
+			let (lineNo,first) = &group[0];
+			let expected = if group.len() == 1 { 1 } else { (first.extent.max(1) as usize - 1) * 2 + 1 };
+			if group.len() != expected || group.iter().any(|(_,m)| m.extent != first.extent) {
+				let msg = format!("line {lineNo}: group {0} has {1} moves, which isn't a supermove of extent {2}",first.group,group.len(),first.extent);
+				return Err(BoardError::Malformed(msg).into());
+			}
+
+			for (_,gameMove) in group {
+				let top = game.resolvePosition(gameMove.source).last().copied();
+				if top != Some(gameMove.card) {
+					return Err(SolverError::UnexpectedCard { position: gameMove.source, expected: gameMove.card });
+				}
+				if !game.isAllowedMove(&gameMove.card, gameMove.target) {
+					return Err(SolverError::IllegalMove { card: gameMove.card, target: gameMove.target });
+				}
+				game.group = gameMove.group;
+				game.moveCard(gameMove.source, gameMove.target, gameMove.extent)?;
+			}
+		}
+
+		return Ok(game);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dealBoard;
+
+	// the deal from seed 2 followed by the given moves, written with the top cards of stacks and the first empty cell filled in
+	// for {S1}, {S2} and {C}
+	fn solutionFor(moves:&[&str]) -> String {
+		let board = dealBoard(2);
+		let top = |i:usize| cardName(board.stacks[i].last(), "".to_string()).trim().to_string();
+		let cell = board.cells.iter().position(|c| c.is_empty()).unwrap() + 1;
+		let mut text = board.toText();
+		for m in moves {
+			text += &format!("move: {0}\n",m.replace("{S1}",&top(0)).replace("{S2}",&top(1)).replace("{C}",&cell.to_string()));
+		}
+		return text;
+	}
+
+	#[test]
+	fn loadsWhatItSaves() {
+		let mut game = Game::fromBoard(dealBoard(2), Tally::default());
+		game.render = false;
+		assert!(game.solve().unwrap());
+
+		let loaded = Game::loadSolution(&game.solutionText(), Tally::default()).unwrap();
+		assert_eq!(loaded.board.toText(), game.board.toText());
+		assert_eq!(loaded.describeHistory(), game.describeHistory());
+	}
+
+	#[test]
+	fn rejectsMovesTheRulesDontAllow() {
+		assert!(Game::loadSolution(&solutionFor(&["0 S1 C{C} 1 {S1}"]), Tally::default()).is_ok());
+
+		let toGoal = solutionFor(&["0 S1 G1 1 {S1}"]);
+		let sameCell = solutionFor(&["0 S1 C{C} 1 {S1}", "1 S2 C{C} 1 {S2}"]);
+		for text in [toGoal, sameCell] {
+			assert!(matches!(Game::loadSolution(&text, Tally::default()), Err(SolverError::IllegalMove { .. })), "{0} should not load", text);
+		}
+	}
+
+	#[test]
+	fn rejectsGroupsThatArentSupermoves() {
+		let extentZero = solutionFor(&["0 S1 C{C} 0 {S1}", "0 S2 S1 0 {S2}"]);
+		let tooShort = solutionFor(&["0 S1 C{C} 2 {S1}", "0 S2 S1 2 {S2}"]);
+		for text in [extentZero, tooShort] {
+			assert!(matches!(Game::loadSolution(&text, Tally::default()), Err(SolverError::InvalidBoard(BoardError::Malformed(_)))), "{0} should not load", text);
+		}
+	}
+
+	#[test]
+	fn rejectsBadPositionsWithoutPanicking() {
+		assert!(matches!(parsePosition("S10"), Ok(Position { stackIndex: 9, stackType: StackType::TABLEAU })));
+		for text in ["", "S", "S0", "S11", "G5", "X1", "♥1", "S♥"] {
+			assert!(parsePosition(text).is_err(), "{0} should not parse", text);
+		}
+	}
+}
@@ -0,0 +1,115 @@
+// Render boards as SVG for documentation and bug reports, and a whole solution as a self-contained HTML page that steps through
+// the positions with next/prev buttons.  Cards use the same style and theme as the terminal ( colorCard by default ), so pictures line up with what the terminal shows.
+
+use terminal::Color;
+
+use crate::{Board, Card, Game};
+use crate::appearance::Appearance;
+use crate::error::SolverError;
+use crate::history::describeSupermove;
+
+const CARD_WIDTH: u32 = 48;
+const CARD_HEIGHT: u32 = 66;
+const GAP: u32 = 8;
+const OVERLAP: u32 = 22; // vertical offset between cards in a tableau column
+
+// colors as they'd appear on a white card face.  White and the default color become black, and yellow is darkened to stay legible
+fn hexColor(color:Color) -> &'static str {
+	match color {
+		Color::DarkRed => "#a00000",
+		Color::Red => "#e83030",
+		Color::DarkBlue => "#10208c",
+		Color::Blue => "#3070e0",
+		Color::Yellow => "#c08000",
+		Color::Cyan => "#0090b0",
+		_ => "#000000"
+	}
+}
+
+fn escapeXml(text:&str) -> String {
+	return text.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;").replace('"',"&quot;");
+}
+
+// a card, or an empty slot outline with a placeholder label
+fn cardSvg(x:u32,y:u32,card:Option<&Card>,placeholder:&str,appearance:&Appearance) -> String {
+	match card {
+		Some(c) => {
+			let name = appearance.label(Some(c), "".to_string());
+			format!("<rect x=\"{x}\" y=\"{y}\" width=\"{CARD_WIDTH}\" height=\"{CARD_HEIGHT}\" rx=\"5\" fill=\"#ffffff\" stroke=\"#555555\"/>\
+				<text x=\"{0}\" y=\"{1}\" fill=\"{2}\" font-family=\"monospace\" font-size=\"15\" font-weight=\"bold\">{3}</text>",
+				x+5,y+17,hexColor(appearance.color(Some(c))),name.trim())
+		},
+		None => format!("<rect x=\"{x}\" y=\"{y}\" width=\"{CARD_WIDTH}\" height=\"{CARD_HEIGHT}\" rx=\"5\" fill=\"none\" stroke=\"#9fc49f\" stroke-dasharray=\"4 3\"/>\
+			<text x=\"{0}\" y=\"{1}\" fill=\"#9fc49f\" font-family=\"monospace\" font-size=\"13\" text-anchor=\"middle\">{placeholder}</text>",
+			x+CARD_WIDTH/2,y+CARD_HEIGHT/2+4)
+	}
+}
+
+// Goals along the top left, cells along the top right, then the 10 stacks fanned downward
+pub(crate) fn boardSvg(board:&Board,appearance:&Appearance) -> String {
+	let columnX = |i:u32| GAP + i*(CARD_WIDTH+GAP);
+	let tableauY = GAP*3 + CARD_HEIGHT;
+	let maxLength = board.stacks.iter().map(|s| s.len()).max().unwrap() as u32;
+	let width = columnX(board.stacks.len() as u32);
+	let height = tableauY + maxLength.max(1).saturating_sub(1)*OVERLAP + CARD_HEIGHT + GAP;
+
+	let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\
+		<rect width=\"100%\" height=\"100%\" fill=\"#1e5b2e\"/>");
+
+	for (i,goalStack) in board.goals.iter().enumerate() {
+		svg += &cardSvg(columnX(i as u32),GAP,goalStack.last(),"goal",appearance);
+	}
+	let cellStart = board.stacks.len() - board.cells.len();
+	for (i,cellStack) in board.cells.iter().enumerate() {
+		svg += &cardSvg(columnX((cellStart+i) as u32),GAP,cellStack.last(),"cell",appearance);
+	}
+	for (col,stack) in board.stacks.iter().enumerate() {
+		if stack.is_empty() {
+			svg += &cardSvg(columnX(col as u32),tableauY,None,"",appearance);
+		}
+		for (row,card) in stack.iter().enumerate() {
+			svg += &cardSvg(columnX(col as u32),tableauY + row as u32*OVERLAP,Some(card),"",appearance);
+		}
+	}
+
+	svg += "</svg>";
+	return svg;
+}
+
+// An HTML page showing every position of a solved game, one supermove per step, with prev/next buttons ( or the arrow keys ).
+// The game is rewound to its start in the process and left at the end position
+pub(crate) fn solutionHtml(game:&mut Game,title:&str) -> Result<String,SolverError> {
+	game.rewind()?;
+	game.render = false;
+
+	let mut frames: Vec<(String,String)> = vec![(boardSvg(&game.board,&game.appearance),"Starting position".to_string())];
+	while game.redo()? {
+		let description = game.supermoves().last().map_or(String::new(), |moves| describeSupermove(moves));
+		frames.push((boardSvg(&game.board,&game.appearance),description));
+	}
+
+	let mut html = format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+		<style>body {{ font-family: sans-serif; background: #f4f4f4; }} .frame {{ display: none; }} .frame.shown {{ display: block; }} \
+		button {{ font-size: 16px; margin-right: 8px; }}</style>\n</head>\n<body>\n<h2>{0}</h2>\n\
+		<p><button id=\"prev\">&larr; Prev</button><button id=\"next\">Next &rarr;</button><span id=\"step\"></span></p>\n",escapeXml(title));
+
+	for (i,(svg,description)) in frames.iter().enumerate() {
+		html += &format!("<div class=\"frame{0}\"><p>{1}</p>{svg}</div>\n",if i == 0 { " shown" } else { "" },escapeXml(description));
+	}
+
+	html += "<script>\n\
+		const frames = document.querySelectorAll('.frame');\n\
+		let current = 0;\n\
+		function show(i) {\n\
+		  current = Math.max(0, Math.min(frames.length - 1, i));\n\
+		  frames.forEach((f, n) => f.classList.toggle('shown', n === current));\n\
+		  document.getElementById('step').textContent = 'Move ' + current + ' of ' + (frames.length - 1);\n\
+		}\n\
+		document.getElementById('prev').onclick = () => show(current - 1);\n\
+		document.getElementById('next').onclick = () => show(current + 1);\n\
+		document.addEventListener('keydown', e => { if (e.key === 'ArrowLeft') show(current - 1); if (e.key === 'ArrowRight') show(current + 1); });\n\
+		show(0);\n\
+		</script>\n</body>\n</html>\n";
+
+	return Ok(html);
+}
@@ -0,0 +1,37 @@
+// Transposition keys that treat symmetric boards as the same position.  The rules never single out a suit: every suit builds down
+// in the tableau and up on its own goal the same way.  So two boards that differ only by swapping the suits around win or lose
+// together, and once the solver has been through one it has nothing to learn from the other.
+//
+// The key ( see canonical.rs ) is built for each of the 24 ways of relabelling the suits and the smallest is kept, so as well as the
+// order of the cells and columns not mattering, neither does which suit is which.
+//
+// In a tournament over the 60 deals from seed 1 this cut the nodes expanded by under 1% ( 1,307,940 to 1,299,300 ), with the same
+// deals won and lost, while trying all 24 relabellings made solving about 12 times slower.
+// Suit-swapped twins are rare in practice: the tableau of a deal is fixed, and a board only has a twin reachable from the same deal
+// when whole runs of two suits happen to mirror each other.  So this is off by default, turned on with --symmetry.
+
+use std::sync::OnceLock;
+
+use crate::Board;
+use crate::canonical;
+
+static PERMUTATIONS: OnceLock<Vec<[u8;4]>> = OnceLock::new();
+
+// every ordering of the four suits, as the new suit for each old one
+fn permutations() -> &'static [[u8;4]] {
+	return PERMUTATIONS.get_or_init(|| {
+		let mut all = Vec::new();
+		for a in 0..4 { for b in 0..4 { for c in 0..4 { for d in 0..4 {
+			let p = [a,b,c,d];
+			if (0..4).all(|suit| p.contains(&suit)) {
+				all.push(p);
+			}
+		}}}}
+		all
+	});
+}
+
+pub(crate) fn canonicalChecksum(board:&Board) -> String {
+	let key = permutations().iter().map(|p| canonical::encode(board, p)).min().unwrap_or_default();
+	return canonical::keyString(&key);
+}
@@ -0,0 +1,157 @@
+// Running several solver configurations over the same deals to see which does best.  Each entrant is a name and the solver options
+// it runs with, given one per line in an entrants file:
+//
+//   # name            options
+//   baseline
+//   lowest-blocked    --ordering lowest-blocked
+//   no-pruning        --no-dead-ends all
+//   quick             --ordering random --ordering-seed 7 --limit 100000
+//
+// Any option the solver takes can go after the name, along with --limit for the abandon threshold.  Without a file the entrants are
+// the move orderings from ordering.rs, with any other solver options given on the command line.  Every entrant plays every deal, deal by deal, so stopping with Ctrl-C still leaves a fair
+// comparison over the deals finished so far.
+//
+//   seahaven tournament [--entrants file] [--games n] [--seed s] [--out file]
+//
+// The table gives each entrant's wins, abandons and losses, its average nodes and time per deal, and wins per second of solving,
+// followed by how many deals each entrant won that each of the others didn't, and which those were.
+
+use std::io::Write;
+use std::time::Instant;
+
+use crate::{dealBoard, optionValue, Game, Tally, ABANDON_THRESHOLD};
+use crate::error::SolverError;
+use crate::options::SolverOptions;
+use crate::ordering::OrderingPolicy;
+use crate::results::Outcome;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Entrant {
+	pub(crate) name: String,
+	pub(crate) options: SolverOptions,
+	pub(crate) limit: u32 // abandon threshold
+}
+
+impl Entrant {
+
+	// one line of an entrants file, or None for a blank line or comment
+	fn parse(line:&str) -> Option<Entrant> {
+		let words: Vec<String> = line.split_whitespace().map(|w| w.to_string()).collect();
+		let name = words.first().filter(|w| !w.starts_with('#'))?;
+		return Some(Entrant {
+			name: name.clone(),
+			options: SolverOptions::fromArgs(&words),
+			limit: optionValue(&words,"--limit").and_then(|l| l.parse::<u32>().ok()).unwrap_or(ABANDON_THRESHOLD)
+		});
+	}
+
+	pub(crate) fn load(path:&str) -> std::io::Result<Vec<Entrant>> {
+		return Ok(std::fs::read_to_string(path)?.lines().filter_map(Entrant::parse).collect());
+	}
+
+	// each move ordering with the rest of the options as given
+	pub(crate) fn orderings(options:SolverOptions) -> Vec<Entrant> {
+		return ["goal-first","fewest-cells","empty-column","lowest-blocked","random"].iter()
+			.filter_map(|name| OrderingPolicy::named(name, 1))
+			.map(|ordering| Entrant {
+				name: ordering.name().to_string(),
+				options: SolverOptions { ordering, ..options },
+				limit: ABANDON_THRESHOLD
+			})
+			.collect();
+	}
+}
+
+// how one entrant did on one deal
+#[derive(Copy, Clone, Debug)]
+struct Play {
+	outcome: Outcome,
+	nodes: u64,
+	seconds: f64
+}
+
+// Play games deals from seeds masterSeed upward with every entrant and write the comparison to out
+pub(crate) fn runTournament(entrants:&[Entrant],games:u32,masterSeed:u64,out:&mut dyn Write) -> std::io::Result<()> {
+	let tally = Tally::default();
+	let mut seeds: Vec<u64> = Vec::new();
+	let mut plays: Vec<Vec<Play>> = vec![Vec::new();entrants.len()]; // plays[entrant][deal]
+
+	'deals: for index in 0..games {
+		let seed = masterSeed.wrapping_add(index as u64);
+		let mut results = Vec::new();
+
+		for entrant in entrants.iter() {
+			let mut game = Game::fromBoard(dealBoard(seed), tally);
+			game.render = false;
+			game.options = entrant.options;
+			game.abandonThreshold = entrant.limit;
+
+			let start = Instant::now();
+			let outcome = match game.solve() {
+				Ok(success) => Outcome::of(&game, success),
+				Err(SolverError::Interrupted) => {
+					eprintln!("Interrupted at seed {seed}, comparing the {index} deals finished");
+					break 'deals;
+				},
+				Err(e) => {
+					eprintln!("Seed {seed}: {0}: solver error: {e}",entrant.name);
+					Outcome::Loser
+				}
+			};
+			results.push(Play { outcome, nodes: game.nodes, seconds: start.elapsed().as_secs_f64() });
+		}
+
+		eprintln!("Played {0} of {games} ( seed {seed} )",index + 1);
+		seeds.push(seed);
+		for (entrant,play) in results.into_iter().enumerate() {
+			plays[entrant].push(play);
+		}
+	}
+
+	return writeTable(entrants, &seeds, &plays, out);
+}
+
+fn writeTable(entrants:&[Entrant],seeds:&[u64],plays:&[Vec<Play>],out:&mut dyn Write) -> std::io::Result<()> {
+	let labels: Vec<String> = entrants.iter().enumerate().map(|(i,e)| format!("{0} {1}",i + 1,e.name)).collect(); // numbered to match the columns below
+	let width = labels.iter().map(|l| l.len()).max().unwrap_or(0).max(8);
+	let deals = seeds.len().max(1) as f64;
+	let won = |entrant:usize,deal:usize| plays[entrant][deal].outcome == Outcome::Winnable;
+
+	writeln!(out,"{0} deals from seed {1}\n",seeds.len(),seeds.first().copied().unwrap_or_default())?;
+	writeln!(out,"{0:<width$}  {1:>5} {2:>9} {3:>5} {4:>12} {5:>10} {6:>10}","entrant","won","abandoned","lost","avg nodes","avg secs","won/sec")?;
+	for (label,results) in labels.iter().zip(plays.iter()) {
+		let count = |outcome:Outcome| results.iter().filter(|p| p.outcome == outcome).count();
+		let nodes: u64 = results.iter().map(|p| p.nodes).sum();
+		let seconds: f64 = results.iter().map(|p| p.seconds).sum();
+		let wonPerSecond = if seconds > 0.0 { count(Outcome::Winnable) as f64 / seconds } else { 0.0 };
+		writeln!(out,"{0:<width$}  {1:>5} {2:>9} {3:>5} {4:>12.0} {5:>10.3} {6:>10.2}",
+			label,count(Outcome::Winnable),count(Outcome::Abandoned),count(Outcome::Loser),nodes as f64 / deals,seconds / deals,wonPerSecond)?;
+	}
+
+	// deals won by the row's entrant and not by the column's
+	writeln!(out,"\nwon by row, not by column\n")?;
+	write!(out,"{0:<width$} ","")?;
+	for column in 0..entrants.len() {
+		write!(out," {0:>4}",column + 1)?;
+	}
+	writeln!(out)?;
+	for (row,label) in labels.iter().enumerate() {
+		write!(out,"{0:<width$} ",label)?;
+		for column in 0..entrants.len() {
+			let only = (0..seeds.len()).filter(|d| won(row,*d) && !won(column,*d)).count();
+			if row == column { write!(out," {0:>4}","-")? } else { write!(out," {only:>4}")? }
+		}
+		writeln!(out)?;
+	}
+
+	let mut first = true;
+	for row in 0..entrants.len() {
+		for column in 0..entrants.len() {
+			let only: Vec<String> = (0..seeds.len()).filter(|d| won(row,*d) && !won(column,*d)).map(|d| seeds[d].to_string()).collect();
+			if only.is_empty() { continue }
+			if first { writeln!(out)?; first = false }
+			writeln!(out,"{0} won and {1} didn't: seeds {2}",entrants[row].name,entrants[column].name,only.join(", "))?;
+		}
+	}
+	return Ok(());
+}
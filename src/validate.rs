@@ -0,0 +1,131 @@
+// Sanity checks for a Board that didn't come from initializeBoard, e.g. one typed in by hand or loaded from a file.
+// The solver assumes every card appears exactly once and that goals are built in order, so anything else is rejected up front
+// rather than surfacing later as an impossible move.
+
+use std::fmt;
+
+use crate::{cardName, Board, Card};
+
+#[derive(Clone, Debug)]
+pub(crate) enum BoardError {
+	Malformed(String), // the text could not be read as a board at all
+	InvalidCard(Card), // suit or value out of range
+	DuplicateCard(Card),
+	MissingCard(Card),
+	InvalidGoalSequence(usize), // goal stack does not run from the Ace upward in a single suit
+	OverfullCell(usize) // a cell can hold at most one card
+}
+
+impl fmt::Display for BoardError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let name = |c:&Card| cardName(Some(c), "".to_string()).trim().to_string();
+		match self {
+			BoardError::Malformed(msg) => write!(f,"malformed board: {msg}"),
+			BoardError::InvalidCard(c) => write!(f,"invalid card suit {0} value {1}",c.suit,c.value),
+			BoardError::DuplicateCard(c) => write!(f,"{0} appears more than once",name(c)),
+			BoardError::MissingCard(c) => write!(f,"{0} is missing",name(c)),
+			BoardError::InvalidGoalSequence(i) => write!(f,"goal {0} is not an ordered run of one suit starting from the Ace",i+1),
+			BoardError::OverfullCell(i) => write!(f,"cell {0} holds more than one card",i+1)
+		}
+	}
+}
+
+impl Board {
+
+	pub(crate) fn validate(&self) -> Result<(),BoardError> {
+
+		for (i,goalStack) in self.goals.iter().enumerate() {
+			for (pos,card) in goalStack.iter().enumerate() {
+				if card.suit != goalStack[0].suit || card.value as usize != pos+1 {
+					return Err(BoardError::InvalidGoalSequence(i));
+				}
+			}
+		}
+
+		for (i,cellStack) in self.cells.iter().enumerate() {
+			if cellStack.len() > 1 { return Err(BoardError::OverfullCell(i)) }
+		}
+
+		// every card must be accounted for exactly once across goals, cells and stacks
+		let mut seen = [[false;13];4];
+		let allCards = self.goals.iter().chain(self.cells.iter()).chain(self.stacks.iter()).flatten();
+		for card in allCards {
+			if card.suit > 3 || card.value < 1 || card.value > 13 { return Err(BoardError::InvalidCard(*card)) }
+
+			let slot = &mut seen[card.suit as usize][card.value as usize - 1];
+			if *slot { return Err(BoardError::DuplicateCard(*card)) }
+			*slot = true;
+		}
+
+		for (suit,values) in seen.iter().enumerate() {
+			for (v,found) in values.iter().enumerate() {
+				if !found { return Err(BoardError::MissingCard(Card { suit: suit as u8, value: v as u8 + 1 })) }
+			}
+		}
+
+		return Ok(());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::dealBoard;
+
+	// a fresh deal with the top card of a stack that isn't an Ace taken off, for moving somewhere it shouldn't be
+	fn dealWithoutTopCard() -> (Board,Card) {
+		let mut board = dealBoard(2);
+		let stack = board.stacks.iter_mut().find(|s| s.last().is_some_and(|c| c.value != 1)).unwrap();
+		let card = stack.pop().unwrap();
+		return (board,card);
+	}
+
+	#[test]
+	fn acceptsADeal() {
+		assert!(dealBoard(2).validate().is_ok());
+	}
+
+	#[test]
+	fn rejectsTextThatIsntABoard() {
+		assert!(matches!(Board::parse("not a board"), Err(BoardError::Malformed(_))));
+	}
+
+	#[test]
+	fn rejectsCardsOutOfRange() {
+		let (mut board,_) = dealWithoutTopCard();
+		board.stacks[0].push(Card { suit: 4, value: 1 });
+		assert!(matches!(board.validate(), Err(BoardError::InvalidCard(Card { suit: 4, value: 1 }))));
+	}
+
+	#[test]
+	fn rejectsDuplicateCards() {
+		let mut board = dealBoard(2);
+		let copy = board.stacks[1][0];
+		board.stacks[0][0] = copy;
+		assert!(matches!(board.validate(), Err(BoardError::DuplicateCard(_))));
+	}
+
+	#[test]
+	fn rejectsMissingCards() {
+		let (board,_) = dealWithoutTopCard();
+		assert!(matches!(board.validate(), Err(BoardError::MissingCard(_))));
+	}
+
+	#[test]
+	fn rejectsGoalsThatDontStartFromTheAce() {
+		let (mut board,card) = dealWithoutTopCard();
+		board.goals[2].push(card);
+		assert!(matches!(board.validate(), Err(BoardError::InvalidGoalSequence(2))));
+	}
+
+	#[test]
+	fn rejectsCellsHoldingMoreThanOneCard() {
+		let mut board = dealBoard(2);
+		let cell = board.cells.iter().position(|c| c.is_empty()).unwrap();
+		for stack in 0..2 {
+			let card = board.stacks[stack].pop().unwrap();
+			board.cells[cell].push(card);
+		}
+		assert!(matches!(board.validate(), Err(BoardError::OverfullCell(i)) if i == cell));
+	}
+}